@@ -1,16 +1,21 @@
-//! A read-only bi-directional multimap.
+//! A bi-directional multimap.
 
-use std::{collections::BTreeSet, fmt};
+use std::{cmp::Ordering, collections::BTreeSet, fmt};
 
 use sorted_iter::{assume::AssumeSortedByItemExt, SortedIterator};
 
 use crate::{sorted, BitMatrix};
 
-/// A read-only bi-directional [multimap].
+/// A bi-directional [multimap].
 ///
 /// This has very good perf for small sets of key and values
 /// that themselves shouldn't take much memory.
 ///
+/// Associations can be added and removed with [`Bimultimap::insert`] and
+/// [`Bimultimap::remove`], but this rebuilds the association matrix each time
+/// a key or value is added or removed. Prefer building it with `collect`
+/// when possible.
+///
 /// Furthermore, not only can you get all `values` associated with a given `key`,
 /// but also all `keys` associated with a given `value`.
 /// See [`Bimultimap::get_keys_of`] and [`Bimultimap::get`].
@@ -70,8 +75,8 @@ use crate::{sorted, BitMatrix};
 ///
 /// [multimap]: https://en.wikipedia.org/wiki/Multimap
 pub struct Bimultimap<K: Eq + Ord, V: Eq + Ord> {
-    sparse_keys: sorted::Box<K>,
-    sparse_values: sorted::Box<V>,
+    sparse_keys: sorted::Vec<K>,
+    sparse_values: sorted::Vec<V>,
     // TODO(feat): When the nº Modify that have Modify dependencies become very
     // large, we should consider a roaring bitmap (#K × #V > 8000)
     // TODO(perf): Also consider storing a raw pointer with no size data,
@@ -83,7 +88,21 @@ pub struct Bimultimap<K: Eq + Ord, V: Eq + Ord> {
     /// Specifically, the column is the index in `sparse_values` of relevant `V`s.
     associations: BitMatrix,
 }
+impl<K: Eq + Ord, V: Eq + Ord> Default for Bimultimap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl<K: Eq + Ord, V: Eq + Ord> Bimultimap<K, V> {
+    /// Create an empty [`Bimultimap`].
+    #[must_use]
+    pub fn new() -> Self {
+        Bimultimap {
+            sparse_keys: sorted::Vec::default(),
+            sparse_values: sorted::Vec::default(),
+            associations: BitMatrix::new_with_size(0, 0),
+        }
+    }
     /// Return indices in `sparse_values` of values associated with key of index `row`.
     #[inline]
     fn mapped_associates_of(&self, row: usize) -> impl Iterator<Item = usize> + '_ {
@@ -92,14 +111,52 @@ impl<K: Eq + Ord, V: Eq + Ord> Bimultimap<K, V> {
     }
     /// All keys present in this map, sorted.
     #[must_use]
-    pub fn keys(&self) -> sorted::Slice<K> {
+    pub fn keys(&self) -> sorted::Slice<'_, K> {
         self.sparse_keys.slice()
     }
     /// All values present in this map, sorted.
     #[must_use]
-    pub fn values(&self) -> sorted::Slice<V> {
+    pub fn values(&self) -> sorted::Slice<'_, V> {
         self.sparse_values.slice()
     }
+    /// Iterate over all associations in this map.
+    ///
+    /// Each association is visited exactly once, sorted by key,
+    /// then by value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::Bimultimap;
+    ///
+    /// let map: Bimultimap<char, i64> = [('T', -5), ('H', 10), ('H', -5)].into_iter().collect();
+    ///
+    /// let assocs: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+    /// assert_eq!(&assocs, &[('H', -5), ('H', 10), ('T', -5)]);
+    /// assert_eq!(map.len(), 3);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.sparse_keys
+            .iter()
+            .enumerate()
+            .flat_map(move |(row, key)| {
+                self.mapped_associates_of(row)
+                    // SAFETY: see `Bimultimap::get`
+                    .map(move |mapped| (key, unsafe { self.sparse_values.get_unchecked(mapped) }))
+            })
+    }
+    /// How many associations there are in this map.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        (0..self.sparse_keys.len())
+            .map(|row| self.mapped_associates_of(row).count())
+            .sum()
+    }
+    /// Whether this map has no associations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sparse_keys.is_empty()
+    }
     /// Get all values associated with `key`.
     pub fn get(&self, key: &K) -> impl SortedIterator<Item = &V> + '_ {
         self.sparse_keys
@@ -124,6 +181,133 @@ impl<K: Eq + Ord, V: Eq + Ord> Bimultimap<K, V> {
             .filter_map(|mapped| self.sparse_keys.get(mapped))
             .assume_sorted_by_item()
     }
+    /// Associate `key` with `value`, in both directions.
+    ///
+    /// Returns `false` if the association already existed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::Bimultimap;
+    ///
+    /// let mut map = Bimultimap::new();
+    /// assert!(map.insert('H', 10));
+    /// assert!(map.insert('H', -5));
+    /// assert!(map.insert('T', -5));
+    /// assert!(!map.insert('T', -5));
+    ///
+    /// assert_eq!(map.get(&'H').copied().collect::<Vec<_>>(), [-5, 10]);
+    /// assert_eq!(map.get_keys_of(&-5).copied().collect::<Vec<_>>(), ['H', 'T']);
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        let (old_width, old_height) = (self.sparse_values.len(), self.sparse_keys.len());
+
+        let (key_i, new_key) = match self.sparse_keys.binary_search(&key) {
+            Ok(key_i) => (key_i, None),
+            Err(_) => {
+                let key_i = self.sparse_keys.insert(key);
+                (key_i, Some(key_i))
+            }
+        };
+        let (value_i, new_value) = match self.sparse_values.binary_search(&value) {
+            Ok(value_i) => (value_i, None),
+            Err(_) => {
+                let value_i = self.sparse_values.insert(value);
+                (value_i, Some(value_i))
+            }
+        };
+        if new_key.is_some() || new_value.is_some() {
+            let shift = |inserted: Option<usize>| {
+                move |i: usize| Some(if inserted.is_some_and(|n| i >= n) { i + 1 } else { i })
+            };
+            self.relayout(old_width, old_height, shift(new_key), shift(new_value));
+        }
+        let width = self.sparse_values.len();
+        let existed = self.associations.bit(width, value_i, key_i);
+        self.associations.enable_bit(width, value_i, key_i).unwrap();
+        !existed
+    }
+    /// Remove the association between `key` and `value`, in both directions.
+    ///
+    /// When `key` or `value` has no more associations, it is removed
+    /// from [`Bimultimap::keys`] or [`Bimultimap::values`].
+    ///
+    /// Returns `false` if the association didn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::Bimultimap;
+    ///
+    /// let mut map: Bimultimap<char, i64> = [('H', 10), ('H', -5), ('T', -5)].into_iter().collect();
+    ///
+    /// assert!(map.remove(&'H', &10));
+    /// assert!(!map.remove(&'H', &10));
+    /// assert_eq!(&*map.values(), &[-5]);
+    ///
+    /// assert!(map.remove(&'T', &-5));
+    /// assert_eq!(&*map.keys(), &['H']);
+    /// assert_eq!(map.get_keys_of(&-5).copied().collect::<Vec<_>>(), ['H']);
+    /// ```
+    pub fn remove(&mut self, key: &K, value: &V) -> bool {
+        let (Ok(key_i), Ok(value_i)) = (
+            self.sparse_keys.binary_search(key),
+            self.sparse_values.binary_search(value),
+        ) else {
+            return false;
+        };
+        let (old_width, old_height) = (self.sparse_values.len(), self.sparse_keys.len());
+        if !self.associations.bit(old_width, value_i, key_i) {
+            return false;
+        }
+        self.associations.disable_bit(old_width, value_i, key_i);
+
+        let key_is_empty = self.mapped_associates_of(key_i).next().is_none();
+        let value_is_empty = self
+            .associations
+            .active_rows_in_column(old_width, value_i)
+            .next()
+            .is_none();
+
+        let removed_key = key_is_empty.then(|| self.sparse_keys.remove(key_i));
+        let removed_value = value_is_empty.then(|| self.sparse_values.remove(value_i));
+
+        if removed_key.is_some() || removed_value.is_some() {
+            let shift = |removed: Option<usize>| {
+                move |i: usize| match removed.map_or(Ordering::Less, |r| i.cmp(&r)) {
+                    Ordering::Less => Some(i),
+                    Ordering::Equal => None,
+                    Ordering::Greater => Some(i - 1),
+                }
+            };
+            let key_i = removed_key.map(|_| key_i);
+            let value_i = removed_value.map(|_| value_i);
+            self.relayout(old_width, old_height, shift(key_i), shift(value_i));
+        }
+        true
+    }
+    /// Rebuild `associations` after keys or values were added or removed.
+    ///
+    /// `key_at` and `value_at` map the old row and column indices to new ones,
+    /// `None` means the row or column was removed.
+    fn relayout(
+        &mut self,
+        old_width: usize,
+        old_height: usize,
+        key_at: impl Fn(usize) -> Option<usize>,
+        value_at: impl Fn(usize) -> Option<usize>,
+    ) {
+        let (width, height) = (self.sparse_values.len(), self.sparse_keys.len());
+        let mut associations = BitMatrix::new_with_size(width, height);
+
+        for (old_y, y) in (0..old_height).filter_map(|y| Some((y, key_at(y)?))) {
+            let row = self.associations.row(old_width, old_y);
+            for x in row.filter_map(&value_at) {
+                associations.enable_bit(width, x, y).unwrap();
+            }
+        }
+        self.associations = associations;
+    }
 }
 impl<K: Eq + Ord + Clone, V: Eq + Ord + Clone> FromIterator<(K, V)> for Bimultimap<K, V> {
     /// Create a [`Bimultimap`] with all associations.
@@ -140,8 +324,8 @@ impl<K: Eq + Ord + Clone, V: Eq + Ord + Clone> FromIterator<(K, V)> for Bimultim
             keys.insert(key);
             values.insert(value);
         }
-        let sparse_keys = sorted::Box::from_sorted_iter(keys.into_iter());
-        let sparse_values = sorted::Box::from_sorted_iter(values.into_iter());
+        let sparse_keys = sorted::Vec::from_sorted_iter(keys.into_iter());
+        let sparse_values = sorted::Vec::from_sorted_iter(values.into_iter());
 
         let mut associations = BitMatrix::new_with_size(sparse_values.len(), sparse_keys.len());
 
@@ -167,3 +351,69 @@ impl<K: Eq + Ord + fmt::Debug, V: Eq + Ord + fmt::Debug> fmt::Debug for Bimultim
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    /// Check that `map` agrees with `model` in both directions.
+    fn assert_consistent(map: &Bimultimap<u8, u8>, model: &BTreeSet<(u8, u8)>) {
+        let keys: BTreeSet<_> = model.iter().map(|(k, _)| *k).collect();
+        let values: BTreeSet<_> = model.iter().map(|(_, v)| *v).collect();
+        assert_eq!(
+            keys.iter().collect::<Vec<_>>(),
+            map.keys().iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            values.iter().collect::<Vec<_>>(),
+            map.values().iter().collect::<Vec<_>>()
+        );
+
+        for key in 0..16 {
+            let expected: Vec<_> = model
+                .iter()
+                .filter(|(k, _)| *k == key)
+                .map(|e| e.1)
+                .collect();
+            assert_eq!(expected, map.get(&key).copied().collect::<Vec<_>>());
+        }
+        for value in 0..16 {
+            let expected: Vec<_> = model
+                .iter()
+                .filter(|(_, v)| *v == value)
+                .map(|e| e.0)
+                .collect();
+            assert_eq!(
+                expected,
+                map.get_keys_of(&value).copied().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn random_insert_remove() {
+        // xorshift, to get a deterministic sequence of operations
+        let mut state = 0x2545_f491_u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let mut map = Bimultimap::new();
+        let mut model = BTreeSet::new();
+
+        for _ in 0..2000 {
+            let random = next();
+            let (key, value) = ((random & 0xf) as u8, (random >> 4 & 0xf) as u8);
+            if random >> 8 & 1 == 0 {
+                assert_eq!(model.insert((key, value)), map.insert(key, value));
+            } else {
+                assert_eq!(model.remove(&(key, value)), map.remove(&key, &value));
+            }
+            assert_consistent(&map, &model);
+        }
+    }
+}
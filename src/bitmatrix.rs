@@ -1,6 +1,6 @@
 //! A [bitset](Bitset) with fixed-size rows.
 
-use std::{fmt, mem};
+use std::fmt;
 
 use crate::{div_ceil, Bitset};
 
@@ -16,16 +16,38 @@ impl BitMatrix {
     /// Note that this might be greater than the `height` given to [`Self::new_with_size`]
     /// due to `BitMatrix` discarding information about actual size.
     ///
-    /// # Panics
-    /// If `Self` is not empty **and** `width` equals `0` (division by zero)
+    /// This is `0` if `width` is `0`.
     #[inline]
     #[must_use]
     pub fn height(&self, width: usize) -> usize {
-        match self.0.bit_len() {
-            0 => 0,
-            total => total / width,
+        match (self.0.bit_len(), width) {
+            (0, _) | (_, 0) => 0,
+            (total, width) => total / width,
         }
     }
+    /// Whether this matrix has no bits at all, for example when created
+    /// with a `width` or `height` of `0`.
+    ///
+    /// A non-empty matrix with all bits disabled is not empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::BitMatrix;
+    /// let matrix = BitMatrix::new_with_size(0, 10);
+    /// assert!(matrix.is_empty());
+    /// assert_eq!(matrix.height(0), 0);
+    /// assert_eq!(matrix.row(0, 3).count(), 0);
+    ///
+    /// let matrix = BitMatrix::new_with_size(3, 2);
+    /// assert!(!matrix.is_empty());
+    /// assert_eq!(matrix.row(3, 100).count(), 0);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.bit_len() == 0
+    }
     /// Iterate over active bits in given `column`.
     ///
     /// # Panics
@@ -34,21 +56,136 @@ impl BitMatrix {
     /// amount of columns)
     #[inline]
     #[must_use]
-    pub fn active_rows_in_column(&self, width: usize, x: usize) -> Column {
+    pub fn active_rows_in_column(&self, width: usize, x: usize) -> Column<'_> {
         assert_ne!(width, 0);
         Column { data: &self.0 .0, width, current_cell: x }
     }
     /// Iterate over the enabled bits of a single row at `y` of this `Bitmatrix`.
     ///
-    /// Assuming the `Bitmatrix` has the provided `width`.
+    /// Assuming the `Bitmatrix` has the provided `width`. Rows past the end
+    /// of the matrix are empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::BitMatrix;
+    /// let rows: [&[usize]; 2] = [&[0, 3], &[1, 2]];
+    /// let matrix = BitMatrix::from_rows(4, rows.map(|r| r.iter().copied()));
+    ///
+    /// assert_eq!(matrix.row(4, 1).collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(matrix.row(4, 20).count(), 0);
+    /// assert_eq!(matrix.row(4, usize::MAX).count(), 0);
+    /// ```
     pub fn row(&self, width: usize, y: usize) -> impl Iterator<Item = usize> + '_ {
-        let start = y * width;
-        let end = (y + 1) * width;
+        let end = y
+            .saturating_add(1)
+            .saturating_mul(width)
+            .min(self.0.bit_len());
+        let start = y.saturating_mul(width).min(end);
 
         self.0
             .ones_in_range(start..end)
             .map(move |i| (i as usize) - start)
     }
+    /// The number of enabled bits in each row of this `BitMatrix`, for rows
+    /// `0..height`.
+    ///
+    /// Assuming the `Bitmatrix` has the provided `width`. Bits are counted
+    /// 32 at a time, rather than visiting each enabled bit.
+    ///
+    /// # Panics
+    /// If `width * height` is larger than the bit size of this matrix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::BitMatrix;
+    /// let rows: [&[usize]; 4] = [&[0, 3], &[], &[0, 1, 2, 3], &[3]];
+    /// let matrix = BitMatrix::from_rows(4, rows.map(|r| r.iter().copied()));
+    ///
+    /// let popcounts = matrix.row_popcounts(4, 4);
+    /// assert_eq!(popcounts.len(), 4);
+    /// assert_eq!(popcounts.collect::<Vec<_>>(), [2, 0, 4, 1]);
+    /// ```
+    pub fn row_popcounts(
+        &self,
+        width: usize,
+        height: usize,
+    ) -> impl ExactSizeIterator<Item = usize> + '_ {
+        (0..height).map(move |y| self.row_popcount(width, y))
+    }
+    /// Count the enabled bits of row `y`, assuming the `BitMatrix` has the
+    /// provided `width`.
+    ///
+    /// This counts whole blocks at a time, without visiting each enabled bit.
+    ///
+    /// # Panics
+    /// If row `y` is past the end of this matrix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::BitMatrix;
+    /// let rows: [&[usize]; 3] = [&[0, 3], &[], &[0, 1, 2, 3]];
+    /// let matrix = BitMatrix::from_rows(4, rows.map(|r| r.iter().copied()));
+    ///
+    /// assert_eq!(matrix.row_popcount(4, 0), 2);
+    /// assert_eq!(matrix.row_popcount(4, 1), 0);
+    /// assert_eq!(matrix.row_popcount(4, 2), 4);
+    /// ```
+    #[must_use]
+    pub fn row_popcount(&self, width: usize, y: usize) -> usize {
+        self.0.ones_in_range(y * width..(y + 1) * width).len()
+    }
+    /// Iterate over the index of rows that have all the bits enabled in `mask`
+    /// enabled (ie: rows that are a superset of `mask`).
+    ///
+    /// Assuming the `Bitmatrix` has the provided `width`. Rows are compared
+    /// to `mask` 32 bits at a time.
+    ///
+    /// If `mask` has enabled bits at or past `width`, no rows match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::{BitMatrix, Bitset};
+    /// let rows: [&[usize]; 4] = [&[0, 3], &[1, 3], &[0, 1, 2, 3], &[3]];
+    /// let matrix = BitMatrix::from_rows(4, rows.map(|r| r.iter().copied()));
+    ///
+    /// let mask = Bitset([0b1010]);
+    /// assert_eq!(matrix.rows_matching(4, &mask).collect::<Vec<_>>(), [1, 2]);
+    ///
+    /// let mask = Bitset([0b1_0000]);
+    /// assert_eq!(matrix.rows_matching(4, &mask).count(), 0);
+    /// ```
+    pub fn rows_matching<'a>(
+        &'a self,
+        width: usize,
+        mask: &'a Bitset<impl AsRef<[u32]>>,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let mask = mask.0.as_ref();
+        let fits_width = |(i, block): (usize, &u32)| {
+            let start = i * u32::BITS as usize;
+            match width.checked_sub(start) {
+                Some(in_width) if in_width < u32::BITS as usize => block >> in_width == 0,
+                Some(_) => true,
+                None => *block == 0,
+            }
+        };
+        let mask_fits = mask.iter().enumerate().all(fits_width);
+        let height = if width == 0 || !mask_fits { 0 } else { self.height(width) };
+
+        (0..height).filter(move |&y| {
+            mask.iter().enumerate().all(|(i, &mask_block)| {
+                if mask_block == 0 {
+                    return true;
+                }
+                let at = y * width + i * u32::BITS as usize;
+                let row_block = self.0.u32_at(at).unwrap_or_else(|partial| partial);
+                row_block & mask_block == mask_block
+            })
+        })
+    }
     /// Enables bit at position `bit`.
     ///
     /// Returns `None` and does nothing if `bit` is out of range.
@@ -62,15 +199,130 @@ impl BitMatrix {
         }
         self.0.enable_bit(width * y + x)
     }
+    /// Disables the bit at column `x` of row `y`.
+    ///
+    /// Assuming the `Bitmatrix` has the provided `width`. Returns `None` and
+    /// does nothing if the bit is out of range.
+    ///
+    /// After this, `self.bit(width, x, y)` is `false`.
+    #[inline]
+    pub fn disable_bit(&mut self, width: usize, x: usize, y: usize) -> Option<()> {
+        if width == 0 {
+            return Some(());
+        }
+        self.0.disable_bit(width * y + x)
+    }
+    /// Disable all bits, keeping the size of this matrix.
+    ///
+    /// This doesn't reallocate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::BitMatrix;
+    /// let rows: [&[usize]; 2] = [&[0, 2], &[1]];
+    /// let mut matrix = BitMatrix::from_rows(3, rows.map(|r| r.iter().copied()));
+    ///
+    /// matrix.clear();
+    /// assert!(!matrix.is_empty());
+    /// assert_eq!(matrix.height(3), 10);
+    /// assert_eq!(matrix.row_popcounts(3, 10).sum::<usize>(), 0);
+    /// ```
+    pub fn clear(&mut self) {
+        self.0 .0.fill(0);
+    }
     /// Create a [`BitMatrix`] with given proportions.
     ///
     /// Note that the total size is the lowest multiple of 32 higher or equal to `width * height`.
+    ///
+    /// When `width` or `height` is `0`, the matrix is [empty](Self::is_empty).
     #[must_use]
     pub fn new_with_size(width: usize, height: usize) -> Self {
         let bit_size = width * height;
-        let u32_size = div_ceil(bit_size, mem::size_of::<u32>());
+        let u32_size = div_ceil(bit_size, u32::BITS as usize);
         BitMatrix(Bitset(vec![0; u32_size].into_boxed_slice()))
     }
+    /// Create a [`BitMatrix`] of given `width` from a list of rows.
+    ///
+    /// Each item of `rows` is a row, listing the enabled columns of that row.
+    /// The height of the matrix is the number of items in `rows`.
+    ///
+    /// # Panics
+    ///
+    /// If any column index is greater or equal to `width`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::BitMatrix;
+    /// let rows: [&[usize]; 3] = [&[0, 3], &[], &[1, 2, 3]];
+    /// let matrix = BitMatrix::from_rows(4, rows.map(|r| r.iter().copied()));
+    ///
+    /// assert!(matrix.bit(4, 3, 0));
+    /// assert!(!matrix.bit(4, 1, 1));
+    /// assert_eq!(matrix.row(4, 2).collect::<Vec<_>>(), [1, 2, 3]);
+    /// assert!(matrix.height(4) >= 3);
+    /// ```
+    #[must_use]
+    pub fn from_rows(
+        width: usize,
+        rows: impl IntoIterator<Item = impl IntoIterator<Item = usize>>,
+    ) -> Self {
+        let mut bits = Bitset(Vec::new());
+        let mut height = 0;
+        for (y, row) in rows.into_iter().enumerate() {
+            for x in row {
+                assert!(
+                    x < width,
+                    "column {x} of row {y} is out of bound (width {width})"
+                );
+                bits.enable_bit_extending(width * y + x);
+            }
+            height = y + 1;
+        }
+        bits.grow_to_bits(width * height);
+        BitMatrix(Bitset(bits.0.into_boxed_slice()))
+    }
+    /// Change the proportions of this matrix to `new_width` and `new_height`,
+    /// assuming it currently has `old_width`.
+    ///
+    /// Existing bits are kept at the same `x, y` position. New rows and columns
+    /// are filled with zeros, while rows and columns outside of the new
+    /// proportions are discarded.
+    ///
+    /// The backing bitset is reallocated and every row is copied to its new
+    /// position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::BitMatrix;
+    /// let rows: [&[usize]; 2] = [&[0, 2], &[1]];
+    /// let mut matrix = BitMatrix::from_rows(3, rows.map(|r| r.iter().copied()));
+    ///
+    /// matrix.resize(3, 5, 3);
+    /// assert_eq!(matrix.row(5, 0).collect::<Vec<_>>(), [0, 2]);
+    /// assert_eq!(matrix.row(5, 1).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(matrix.row(5, 2).count(), 0);
+    ///
+    /// matrix.enable_bit(5, 4, 2).unwrap();
+    /// matrix.resize(5, 2, 3);
+    /// assert_eq!(matrix.row(2, 0).collect::<Vec<_>>(), [0]);
+    /// assert_eq!(matrix.row(2, 1).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(matrix.row(2, 2).count(), 0);
+    /// ```
+    pub fn resize(&mut self, old_width: usize, new_width: usize, new_height: usize) {
+        let u32_size = div_ceil(new_width * new_height, u32::BITS as usize);
+        let mut resized = Bitset(vec![0; u32_size].into_boxed_slice());
+
+        let old_height = if old_width == 0 { 0 } else { self.height(old_width) };
+        for y in 0..old_height.min(new_height) {
+            for x in self.row(old_width, y).take_while(|x| *x < new_width) {
+                resized.enable_bit(new_width * y + x);
+            }
+        }
+        *self = BitMatrix(resized);
+    }
 
     /// `true` if bit at position `x, y` in matrix is enabled.
     ///
@@ -85,7 +337,7 @@ impl BitMatrix {
     ///
     /// [pdf]: https://unicode.org/charts/PDF/U1FB00.pdf
     #[must_use]
-    pub const fn sextant_display(&self, width: usize, height: usize) -> SextantDisplay {
+    pub const fn sextant_display(&self, width: usize, height: usize) -> SextantDisplay<'_> {
         SextantDisplay { matrix: self, width, height }
     }
 }
@@ -119,7 +371,8 @@ impl Iterator for Column<'_> {
     }
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let upper = self.data.len().saturating_sub(self.current_cell) / self.width;
+        let bit_len = self.data.len() * u32::BITS as usize;
+        let upper = div_ceil(bit_len.saturating_sub(self.current_cell), self.width);
         (0, Some(upper))
     }
     #[inline]
@@ -1,12 +1,17 @@
 //! A slice of `u32` accessed on the bit level.
 
-use std::{fmt, iter, ops::Range, ops::RangeBounds};
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::mem;
+use core::{cmp::Ordering, fmt, iter, ops::Range, ops::RangeBounds};
 
+#[cfg(feature = "std")]
 use sorted_iter::sorted_iterator::SortedByItem;
 
 use crate::{div_ceil, safe_n_mask};
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests;
 
 trait BlockT {
@@ -97,7 +102,8 @@ pub struct Bitset<B: AsRef<[u32]>>(pub B);
 
 /// A dynamic size slice allowing mutable extension to its own size.
 ///
-/// This is used by the [`Bitset::enable_bit_extending`] method.
+/// This is used by the [`Bitset::enable_bit_extending`] and
+/// [`Bitset::enable_bit_exact`] methods.
 ///
 /// This is implemented on `Vec`, and `SmallVec` with the `smallvec` feature
 /// enabled.
@@ -109,8 +115,19 @@ pub trait ExtendBlocks: AsMut<[u32]> + AsRef<[u32]> {
     /// Add `extra_blocks` of zeroed `u32`s to this slice, so that the new length
     /// is `self.len() + extra_blocks`.
     fn extend_blocks(&mut self, extra_blocks: usize);
+
+    /// Add exactly `extra_blocks` of zeroed `u32`s to this slice.
+    ///
+    /// Unlike [`ExtendBlocks::extend_blocks`], the new length is always exactly
+    /// `self.len() + extra_blocks`. The default implementation calls
+    /// `extend_blocks`, implementations that over-allocate in `extend_blocks`
+    /// should override it.
+    fn extend_blocks_exact(&mut self, extra_blocks: usize) {
+        self.extend_blocks(extra_blocks);
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl ExtendBlocks for Box<[u32]> {
     /// Extend this `Box<[u32]>` to `(old_len + extra_blocks).next_pow2()`.
     ///
@@ -118,22 +135,123 @@ impl ExtendBlocks for Box<[u32]> {
     fn extend_blocks(&mut self, extra_blocks: usize) {
         let old_len = self.len();
         let new_len = (old_len + extra_blocks).next_power_of_two().max(8);
-        let mut self_vec = std::mem::take(self).into_vec();
+        let mut self_vec = mem::take(self).into_vec();
+
+        self_vec.extend(iter::repeat_n(0, new_len - old_len));
+        *self = self_vec.into();
+    }
+    fn extend_blocks_exact(&mut self, extra_blocks: usize) {
+        let mut self_vec = mem::take(self).into_vec();
 
-        self_vec.extend(iter::repeat(0).take(new_len - old_len));
+        self_vec.reserve_exact(extra_blocks);
+        self_vec.extend(iter::repeat_n(0, extra_blocks));
         *self = self_vec.into();
     }
 }
 
+#[cfg(feature = "alloc")]
 impl ExtendBlocks for Vec<u32> {
     fn extend_blocks(&mut self, extra_blocks: usize) {
-        self.extend(iter::repeat(0).take(extra_blocks));
+        self.extend(iter::repeat_n(0, extra_blocks));
     }
 }
 #[cfg(feature = "smallvec")]
 impl<A: smallvec::Array<Item = u32>> ExtendBlocks for smallvec::SmallVec<A> {
     fn extend_blocks(&mut self, extra_blocks: usize) {
-        self.extend(iter::repeat(0).take(extra_blocks));
+        self.extend(iter::repeat_n(0, extra_blocks));
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Bitset<Vec<u32>> {
+    /// Create an empty `Bitset` with enough capacity to store `bits` bits
+    /// without reallocating.
+    ///
+    /// Note that capacity is distinct from length, the returned `Bitset` has
+    /// a [`Bitset::bit_len`] of `0`. It grows as you add bits with
+    /// [`Bitset::enable_bit_extending`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset::with_capacity_bits(100);
+    /// assert_eq!(bitset.bit_len(), 0);
+    /// assert!(bitset.0.capacity() >= 4);
+    ///
+    /// bitset.enable_bit_extending(99);
+    /// assert_eq!(bitset.bit_len(), 128);
+    /// ```
+    #[must_use]
+    pub fn with_capacity_bits(bits: usize) -> Self {
+        Bitset(Vec::with_capacity(div_ceil(bits, u32::BITS64)))
+    }
+    /// Reserve capacity for at least `additional` more bits than
+    /// [`Bitset::bit_len`].
+    ///
+    /// Like [`Bitset::with_capacity_bits`], this doesn't change `bit_len`.
+    pub fn reserve_bits(&mut self, additional: usize) {
+        self.0.reserve(div_ceil(additional, u32::BITS64));
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Bitset<Box<[u32]>> {
+    /// Create a `Bitset` with the bits in the sorted `iter` enabled.
+    ///
+    /// Since `iter` is sorted, blocks are appended one after the other,
+    /// each block is written once. The result is identical to collecting
+    /// `iter` with `FromIterator`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0xf0f0_00ff_u32, 0, 0xfff0_0f0f]);
+    ///
+    /// let copy = Bitset::from_sorted_iter(bitset.ones());
+    /// assert_eq!(copy.0.as_ref(), bitset.0);
+    ///
+    /// let collected: Bitset<Box<[u32]>> = bitset.ones().collect();
+    /// assert_eq!(copy, collected);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_sorted_iter(iter: impl Iterator<Item = u32> + SortedByItem) -> Self {
+        let mut blocks = Vec::new();
+        let mut current = 0;
+        for bit in iter {
+            let block = (bit / u32::BITS) as usize;
+            // `current` is the block at index `blocks.len()`
+            if block > blocks.len() {
+                blocks.push(mem::take(&mut current));
+                blocks.resize(block, 0);
+            }
+            current |= 1 << (bit % u32::BITS);
+        }
+        if current != 0 {
+            blocks.push(current);
+        }
+        Bitset(blocks.into_boxed_slice())
+    }
+    /// Create a `Bitset` where bit `n` is enabled if `bools[n]` is `true`.
+    ///
+    /// The bitset has exactly `bools.len()` bits rounded up to a multiple
+    /// of 32, see [`Bitset::to_bools`] for the inverse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset::from_bools(&[true, false, false, true, true]);
+    ///
+    /// assert_eq!(bitset.0.as_ref(), &[0b11001]);
+    /// assert_eq!(bitset.to_bools(5), [true, false, false, true, true]);
+    ///
+    /// assert_eq!(Bitset::from_bools(&[false; 33]).bit_len(), 64);
+    /// ```
+    #[must_use]
+    pub fn from_bools(bools: &[bool]) -> Self {
+        bools.iter().copied().collect()
     }
 }
 
@@ -145,6 +263,7 @@ impl<B: ExtendBlocks> Bitset<B> {
     /// # Example
     ///
     /// ```
+    /// # #[cfg(feature = "alloc")] {
     /// # use datazoo::Bitset;
     /// let mut as_vec = Bitset(vec![]);
     /// assert!(as_vec.enable_bit(64).is_none());
@@ -156,15 +275,18 @@ impl<B: ExtendBlocks> Bitset<B> {
     /// assert!(as_vec.enable_bit(64).is_some());
     /// assert!(as_vec.bit(64));
     /// assert_eq!(as_vec.0.len(), 3);
+    /// # }
     /// ```
     /// Note that you can use this with `Box<[u32]>`:
     /// ```
+    /// # #[cfg(feature = "alloc")] {
     /// # use datazoo::Bitset;
     /// let mut as_box = Bitset(Box::<[u32]>::default());
     /// as_box.enable_bit_extending(73);
     /// assert!(as_box.bit(73));
     /// assert!(as_box.enable_bit(64).is_some());
     /// assert!(as_box.bit(64));
+    /// # }
     /// ```
     pub fn enable_bit_extending(&mut self, bit: usize) {
         let block = bit / u32::BITS64;
@@ -178,9 +300,450 @@ impl<B: ExtendBlocks> Bitset<B> {
         let blocks = self.0.as_mut();
         blocks[block] |= 1 << offset;
     }
+    /// Enable `bit`, extending `B` with exactly as many blocks as needed to
+    /// hold it.
+    ///
+    /// Unlike [`Bitset::enable_bit_extending`], this never over-allocates,
+    /// even for `Box<[u32]>`, see [`ExtendBlocks::extend_blocks_exact`].
+    ///
+    /// # Panics
+    ///
+    /// When `bit` is `usize::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset(Box::<[u32]>::default());
+    ///
+    /// bitset.enable_bit_exact(73);
+    /// assert!(bitset.bit(73));
+    /// assert_eq!(bitset.0.len(), 3);
+    ///
+    /// bitset.enable_bit_exact(10);
+    /// assert_eq!(bitset.ones().collect::<Vec<_>>(), [10, 73]);
+    /// assert_eq!(bitset.0.len(), 3);
+    /// # }
+    /// ```
+    pub fn enable_bit_exact(&mut self, bit: usize) {
+        let bit_len = bit.checked_add(1).expect("bit index overflows usize");
+        self.grow_to_bits(bit_len);
+        let blocks = self.0.as_mut();
+        blocks[bit / u32::BITS64] |= 1 << (bit % u32::BITS64);
+    }
+    /// Extend this bitset with zeros, so that it holds exactly
+    /// `div_ceil(bit_len, 32)` blocks.
+    ///
+    /// Unlike [`Bitset::enable_bit_extending`], this doesn't round up the
+    /// number of blocks to the next power of two for `Box<[u32]>`.
+    ///
+    /// This never shrinks the bitset, if it already has enough blocks,
+    /// this does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset(vec![0b1].into_boxed_slice());
+    ///
+    /// bitset.grow_to_bits(100);
+    /// assert_eq!(bitset.bit_len(), 128);
+    /// assert!(bitset.bit(0));
+    ///
+    /// bitset.grow_to_bits(10);
+    /// assert_eq!(bitset.bit_len(), 128);
+    /// # }
+    /// ```
+    pub fn grow_to_bits(&mut self, bit_len: usize) {
+        let blocks_len = self.0.as_ref().len();
+        let new_blocks_len = div_ceil(bit_len, u32::BITS64);
+        if new_blocks_len > blocks_len {
+            self.0.extend_blocks_exact(new_blocks_len - blocks_len);
+        }
+    }
+    /// Enable all bits at `sorted_indices`, extending `B` once if necessary.
+    ///
+    /// This is equivalent to calling [`Bitset::enable_bit_extending`] on each
+    /// index, but `B` is only extended once, and when `sorted_indices` is
+    /// sorted, each block is written to only once. Unsorted indices are
+    /// still all enabled, only slower.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset(vec![0b1]);
+    /// bitset.enable_all(&[3, 4, 33, 70]);
+    ///
+    /// assert_eq!(bitset.ones().collect::<Vec<_>>(), [0, 3, 4, 33, 70]);
+    /// assert_eq!(bitset.0.len(), 3);
+    /// # }
+    /// ```
+    pub fn enable_all(&mut self, sorted_indices: &[u32]) {
+        if let Some(max) = sorted_indices.iter().max() {
+            let blocks_len = self.0.as_ref().len();
+            let block = *max as usize / u32::BITS64;
+            if block >= blocks_len {
+                self.0.extend_blocks(block - blocks_len + 1);
+            }
+        }
+        self.enable_sorted(sorted_indices.iter().copied());
+    }
+    /// Enable all `bits`, extending `B` once if necessary, returning how
+    /// many blocks were added to `B`.
+    ///
+    /// Like [`Bitset::enable_all`], but accepts any iterator, in any order.
+    /// `bits` is iterated twice: once to find the largest bit and grow `B` to
+    /// hold it, and once to enable the bits. This doesn't allocate besides
+    /// extending `B`. The returned count includes the over-allocation of
+    /// [`ExtendBlocks::extend_blocks`], for example for `Box<[u32]>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset(vec![0b1]);
+    ///
+    /// assert_eq!(bitset.enable_extending_all([70, 3, 33]), 2);
+    /// assert_eq!(bitset.ones().collect::<Vec<_>>(), [0, 3, 33, 70]);
+    ///
+    /// assert_eq!(bitset.enable_extending_all([4, 95]), 0);
+    /// assert_eq!(bitset.enable_extending_all([]), 0);
+    /// # }
+    /// ```
+    pub fn enable_extending_all(
+        &mut self,
+        bits: impl IntoIterator<Item = usize, IntoIter: Clone>,
+    ) -> usize {
+        let bits = bits.into_iter();
+        let blocks_len = self.0.as_ref().len();
+        if let Some(max) = bits.clone().max() {
+            let block = max / u32::BITS64;
+            if block >= blocks_len {
+                self.0.extend_blocks(block - blocks_len + 1);
+            }
+        }
+        let blocks = self.0.as_mut();
+        for bit in bits {
+            blocks[bit / u32::BITS64] |= 1 << (bit % u32::BITS64);
+        }
+        blocks.len() - blocks_len
+    }
+    /// Enable all bits enabled in `other`, block-wise (`self |= other`),
+    /// extending `self` to hold the enabled bits of `other`.
+    ///
+    /// See [`Bitset::or_with`] for a version that doesn't extend `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset(vec![0b0011]);
+    /// bitset.or_with_extending(&Bitset([0b0110, 0, 0b1, 0]));
+    ///
+    /// assert_eq!(bitset.0, [0b0111, 0, 0b1]);
+    /// # }
+    /// ```
+    pub fn or_with_extending(&mut self, other: &Bitset<impl AsRef<[u32]>>) {
+        self.grow_to_blocks(other.0.as_ref());
+        self.or_with(other);
+    }
+    /// Toggle all bits enabled in `other`, block-wise (`self ^= other`),
+    /// extending `self` to hold the enabled bits of `other`.
+    ///
+    /// See [`Bitset::xor_with`] for a version that doesn't extend `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset(vec![0b0011]);
+    /// bitset.xor_with_extending(&Bitset([0b0110, 0, 0b1, 0]));
+    ///
+    /// assert_eq!(bitset.0, [0b0101, 0, 0b1]);
+    /// # }
+    /// ```
+    pub fn xor_with_extending(&mut self, other: &Bitset<impl AsRef<[u32]>>) {
+        self.grow_to_blocks(other.0.as_ref());
+        self.xor_with(other);
+    }
+    /// Extend `self` so that it is at least as long as `other` without its
+    /// trailing zero blocks.
+    fn grow_to_blocks(&mut self, other: &[u32]) {
+        let other_len = other.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        self.grow_to_bits(other_len * u32::BITS64);
+    }
 }
 
 impl<B: AsRef<[u32]> + AsMut<[u32]>> Bitset<B> {
+    /// The underlying `u32` blocks of this bitset, mutably.
+    ///
+    /// Bit `i` of the bitset is bit `i % 32` of block `i / 32`. See
+    /// [`Bitset::blocks`] for the immutable version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset([0_u32; 2]);
+    /// bitset.blocks_mut()[1] = 0b101;
+    ///
+    /// assert_eq!(bitset.ones().collect::<Vec<_>>(), [32, 34]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn blocks_mut(&mut self) -> &mut [u32] {
+        self.0.as_mut()
+    }
+    /// Enable all bits at `sorted_indices` that are within the bitset.
+    ///
+    /// Indices greater or equal to [`Bitset::bit_len`] are skipped. Like
+    /// [`Bitset::enable_all`], when `sorted_indices` is sorted, each block
+    /// is written to only once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset([0_u32; 2]);
+    /// bitset.enable_all_in_bounds(&[3, 4, 33, 70]);
+    ///
+    /// assert_eq!(bitset.ones().collect::<Vec<_>>(), [3, 4, 33]);
+    /// ```
+    pub fn enable_all_in_bounds(&mut self, sorted_indices: &[u32]) {
+        let bit_len = self.bit_len();
+        let in_bounds = sorted_indices.iter().copied();
+        self.enable_sorted(in_bounds.filter(|i| (*i as usize) < bit_len));
+    }
+    /// Enable all bits at `indices`, OR-ing consecutive indices in the
+    /// same block before writing it.
+    ///
+    /// All `indices` must be within the bitset.
+    fn enable_sorted(&mut self, indices: impl Iterator<Item = u32>) {
+        let blocks = self.0.as_mut();
+        let mut current: Option<(usize, u32)> = None;
+        for index in indices {
+            let block = index as usize / u32::BITS64;
+            let bit = 1 << (index % u32::BITS);
+            match &mut current {
+                Some((current_block, mask)) if *current_block == block => *mask |= bit,
+                _ => {
+                    if let Some((current_block, mask)) = current {
+                        blocks[current_block] |= mask;
+                    }
+                    current = Some((block, bit));
+                }
+            }
+        }
+        if let Some((current_block, mask)) = current {
+            blocks[current_block] |= mask;
+        }
+    }
+    /// Enable all bits enabled in `other`, block-wise (`self |= other`).
+    ///
+    /// Bits of `other` past the end of `self` are ignored. The length of
+    /// `self` never changes, use [`Bitset::or_with_extending`] to extend it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset([0b0011, 0]);
+    /// bitset.or_with(&Bitset([0b0110, 0b1, 0b1]));
+    ///
+    /// assert_eq!(bitset.0, [0b0111, 0b1]);
+    /// ```
+    pub fn or_with(&mut self, other: &Bitset<impl AsRef<[u32]>>) {
+        for (block, other) in self.0.as_mut().iter_mut().zip(other.0.as_ref()) {
+            *block |= other;
+        }
+    }
+    /// Toggle all bits enabled in `other`, block-wise (`self ^= other`).
+    ///
+    /// Bits of `other` past the end of `self` are ignored. The length of
+    /// `self` never changes, use [`Bitset::xor_with_extending`] to extend it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset([0b0011, 0]);
+    /// bitset.xor_with(&Bitset([0b0110, 0b1, 0b1]));
+    ///
+    /// assert_eq!(bitset.0, [0b0101, 0b1]);
+    /// ```
+    ///
+    /// Use [`Bitset::diff_indices`] before toggling to know which bits will
+    /// change.
+    #[doc(alias = "toggle_where", alias = "symmetric_difference_update")]
+    pub fn xor_with(&mut self, other: &Bitset<impl AsRef<[u32]>>) {
+        for (block, other) in self.0.as_mut().iter_mut().zip(other.0.as_ref()) {
+            *block ^= other;
+        }
+    }
+    /// Disable all bits not enabled in `other`, block-wise (`self &= other`).
+    ///
+    /// Blocks missing from `other` are treated as zero, so bits of `self`
+    /// past the end of `other` are disabled. The length of `self` never
+    /// changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset([0b0011, 0b1, 0b1]);
+    /// bitset.and_with(&Bitset([0b0110, 0b1]));
+    ///
+    /// assert_eq!(bitset.0, [0b0010, 0b1, 0]);
+    /// ```
+    pub fn and_with(&mut self, other: &Bitset<impl AsRef<[u32]>>) {
+        let other = other.0.as_ref().iter().chain(iter::repeat(&0));
+        for (block, other) in self.0.as_mut().iter_mut().zip(other) {
+            *block &= other;
+        }
+    }
+    /// Cyclically move all bits `n` positions toward higher indices, bits
+    /// moved past [`Bitset::bit_len`] wrap around to the lowest indices.
+    ///
+    /// Bit `i` moves to `(i + n) % bit_len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset([0x8000_0001, 0x0000_0010]);
+    ///
+    /// bitset.rotate_left(4);
+    /// assert_eq!(bitset.0, [0x0000_0010, 0x0000_0108]);
+    ///
+    /// bitset.rotate_left(60);
+    /// assert_eq!(bitset.0, [0x8000_0001, 0x0000_0010]);
+    ///
+    /// bitset.rotate_left(64);
+    /// assert_eq!(bitset.0, [0x8000_0001, 0x0000_0010]);
+    /// ```
+    pub fn rotate_left(&mut self, n: usize) {
+        let bit_len = self.bit_len();
+        if bit_len == 0 {
+            return;
+        }
+        let n = n % bit_len;
+        let blocks = self.0.as_mut();
+        blocks.rotate_right(n / u32::BITS64);
+
+        let shift = (n % u32::BITS64) as u32;
+        if shift == 0 {
+            return;
+        }
+        let mut carry = blocks[blocks.len() - 1] >> (u32::BITS - shift);
+        for block in blocks {
+            let next_carry = *block >> (u32::BITS - shift);
+            *block = (*block << shift) | carry;
+            carry = next_carry;
+        }
+    }
+    /// Cyclically move all bits `n` positions toward lower indices, bits
+    /// moved before `0` wrap around to the highest indices.
+    ///
+    /// This is the inverse of [`Bitset::rotate_left`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset([0x8000_0001, 0x0000_0010]);
+    ///
+    /// bitset.rotate_right(1);
+    /// assert_eq!(bitset.0, [0x4000_0000, 0x8000_0008]);
+    /// ```
+    pub fn rotate_right(&mut self, n: usize) {
+        let bit_len = self.bit_len();
+        if bit_len == 0 {
+            return;
+        }
+        self.rotate_left(bit_len - n % bit_len);
+    }
+    /// Move all bits `n` positions toward higher indices, bits moved past
+    /// [`Bitset::bit_len`] are discarded, and the lowest `n` bits are disabled.
+    ///
+    /// If `n >= bit_len`, all bits are disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset(vec![0x8000_0001, 0x8000_0010]);
+    ///
+    /// bitset.shift_left(4);
+    /// assert_eq!(bitset.0, [0x0000_0010, 0x0000_0108]);
+    ///
+    /// bitset.shift_left(64);
+    /// assert_eq!(bitset.0, [0, 0]);
+    /// ```
+    pub fn shift_left(&mut self, n: usize) {
+        let blocks = self.0.as_mut();
+        let (block_shift, shift) = (n / u32::BITS64, (n % u32::BITS64) as u32);
+        if block_shift >= blocks.len() {
+            blocks.fill(0);
+            return;
+        }
+        blocks.copy_within(..blocks.len() - block_shift, block_shift);
+        blocks[..block_shift].fill(0);
+
+        if shift == 0 {
+            return;
+        }
+        let mut carry = 0;
+        for block in &mut blocks[block_shift..] {
+            let next_carry = *block >> (u32::BITS - shift);
+            *block = (*block << shift) | carry;
+            carry = next_carry;
+        }
+    }
+    /// Move all bits `n` positions toward lower indices, bits moved before `0`
+    /// are discarded, and the highest `n` bits are disabled.
+    ///
+    /// If `n >= bit_len`, all bits are disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let mut bitset = Bitset(vec![0x8000_0001, 0x8000_0010]);
+    ///
+    /// bitset.shift_right(1);
+    /// assert_eq!(bitset.0, [0x4000_0000, 0x4000_0008]);
+    ///
+    /// bitset.shift_right(33);
+    /// assert_eq!(bitset.0, [0x2000_0004, 0]);
+    /// ```
+    pub fn shift_right(&mut self, n: usize) {
+        let blocks = self.0.as_mut();
+        let (block_shift, shift) = (n / u32::BITS64, (n % u32::BITS64) as u32);
+        if block_shift >= blocks.len() {
+            blocks.fill(0);
+            return;
+        }
+        let len = blocks.len();
+        blocks.copy_within(block_shift.., 0);
+        blocks[len - block_shift..].fill(0);
+
+        if shift == 0 {
+            return;
+        }
+        let mut carry = 0;
+        for block in blocks[..len - block_shift].iter_mut().rev() {
+            let next_carry = *block << (u32::BITS - shift);
+            *block = (*block >> shift) | carry;
+            carry = next_carry;
+        }
+    }
     /// Enables bit at position `bit`.
     ///
     /// Returns `None` and does nothing if `bit` is out of range.
@@ -283,6 +846,249 @@ impl<B: AsRef<[u32]>> Bitset<B> {
     pub fn bit_len(&self) -> usize {
         self.0.as_ref().len() * u32::BITS64
     }
+    /// The underlying `u32` blocks of this bitset.
+    ///
+    /// Bit `i` of the bitset is bit `i % 32` of block `i / 32`.
+    #[inline]
+    #[must_use]
+    pub fn blocks(&self) -> &[u32] {
+        self.0.as_ref()
+    }
+    /// Split [`Bitset::blocks`] into an unaligned head, a body of `u64` words
+    /// and an unaligned tail, as [`slice::align_to`] does.
+    ///
+    /// This is a building block to process blocks two at a time, for example
+    /// with SIMD. Concatenating the blocks of `head`, of each word of `body`
+    /// and of `tail` gives back [`Bitset::blocks`].
+    ///
+    /// # Alignment
+    ///
+    /// The split point depends on the address of the blocks, not on their
+    /// values: `head` is empty when the blocks start on an 8 bytes boundary,
+    /// otherwise it holds the first block. Two bitsets with the same blocks
+    /// may be split differently, and the same bitset may be split differently
+    /// after being moved to a new allocation. `head` and `tail` have at most
+    /// one block each.
+    ///
+    /// # Byte order
+    ///
+    /// Each word of `body` is two consecutive blocks reinterpreted as a `u64`
+    /// in native byte order. On little endian targets, the first block is in
+    /// the low 32 bits, so bit `i` of the word is bit `i` of the pair. On big
+    /// endian targets, the first block is in the high 32 bits. Use
+    /// [`u64::to_ne_bytes`] to get back the blocks of a word on any target.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0x1, 0x2, 0x3, 0x4, 0x5]);
+    /// let (head, body, tail) = bitset.words64();
+    ///
+    /// assert!(head.len() <= 1 && tail.len() <= 1);
+    /// assert_eq!(head.len() + body.len() * 2 + tail.len(), 5);
+    ///
+    /// // Counting enabled bits doesn't depend on the split.
+    /// let count = |blocks: &[u32]| blocks.iter().map(|b| b.count_ones()).sum::<u32>();
+    /// let body_count = body.iter().map(|w| w.count_ones()).sum::<u32>();
+    /// assert_eq!(count(head) + body_count + count(tail), 7);
+    /// ```
+    #[doc(alias = "align_to_u64")]
+    #[inline]
+    #[must_use]
+    pub fn words64(&self) -> (&[u32], &[u64], &[u32]) {
+        // SAFETY: any two `u32` form a valid `u64`.
+        unsafe { self.0.as_ref().align_to::<u64>() }
+    }
+    /// Iterate over consecutive `n`-bits fields of this bitset.
+    ///
+    /// Each field is read as with [`Bitset::n_at`], the first field starts at
+    /// bit 0, the second at bit `n`, etc. When [`Bitset::bit_len`] is not
+    /// a multiple of `n`, the trailing partial field is skipped.
+    ///
+    /// # Panics
+    /// If `n` is `0` or larger than `32`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0x8765_4321, 0x0000_00ff]);
+    ///
+    /// let nibbles: Vec<_> = bitset.iter_n_bit_values(4).take(10).collect();
+    /// assert_eq!(nibbles, [1, 2, 3, 4, 5, 6, 7, 8, 0xf, 0xf]);
+    ///
+    /// // 64 bits contain 6 fields of 10 bits, the last 4 bits are skipped.
+    /// assert_eq!(bitset.iter_n_bit_values(10).count(), 6);
+    /// ```
+    pub fn iter_n_bit_values(&self, n: u32) -> impl Iterator<Item = u32> + '_ {
+        assert!(
+            n != 0 && n <= u32::BITS,
+            "Can't iterate over {n}-bits values"
+        );
+        let width = n as usize;
+        (0..self.bit_len() / width).map_while(move |i| self.n_at(n, i * width))
+    }
+    /// Like [`Bitset::iter_n_bit_values`], but for fields up to 64 bits wide,
+    /// read as with [`Bitset::n64_at`].
+    ///
+    /// # Panics
+    /// If `n` is `0` or larger than `64`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0x8765_4321, 0x0000_00ff, 0xffff_0000]);
+    ///
+    /// let fields: Vec<_> = bitset.iter_n64_bit_values(40).collect();
+    /// assert_eq!(fields, [0xff_8765_4321, 0]);
+    ///
+    /// let fields: Vec<_> = bitset.iter_n64_bit_values(48).collect();
+    /// assert_eq!(fields, [0x00ff_8765_4321, 0xffff_0000_0000]);
+    /// ```
+    pub fn iter_n64_bit_values(&self, n: u32) -> impl Iterator<Item = u64> + '_ {
+        assert!(
+            n != 0 && n <= u64::BITS,
+            "Can't iterate over {n}-bits values"
+        );
+        let width = n as usize;
+        (0..self.bit_len() / width).map_while(move |i| self.n64_at(n, i * width))
+    }
+    /// Iterate over every bit offset `at` where the `n` bits starting at `at`
+    /// equal `pattern`, ie: where `self.n_at(n, at) == Some(pattern)`.
+    ///
+    /// This is a sliding window, so matches may overlap. If `pattern` has
+    /// enabled bits at or past `n`, nothing matches.
+    ///
+    /// # Panics
+    /// If `n` is `0` or larger than `32`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0b0111_0010_1101]);
+    ///
+    /// let matches: Vec<_> = bitset.find_pattern(2, 0b11).collect();
+    /// assert_eq!(matches, [2, 8, 9]);
+    ///
+    /// let matches: Vec<_> = bitset.find_pattern(3, 0b101).collect();
+    /// assert_eq!(matches, [0, 3]);
+    /// ```
+    pub fn find_pattern(&self, n: u32, pattern: u32) -> impl Iterator<Item = usize> + '_ {
+        assert!(
+            n != 0 && n <= u32::BITS,
+            "Can't search for {n}-bits patterns"
+        );
+        let end = (self.bit_len() + 1).saturating_sub(n as usize);
+        (0..end).filter(move |at| self.n_at(n, *at) == Some(pattern))
+    }
+    /// Iterate over maximal ranges of consecutive enabled bits, in order.
+    ///
+    /// Runs may span several blocks. Use [`Bitset::zero_runs`] for ranges of
+    /// disabled bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0xf000_0f0e, 0x8000_0001]);
+    ///
+    /// let runs: Vec<_> = bitset.runs().collect();
+    /// assert_eq!(runs, [1..4, 8..12, 28..33, 63..64]);
+    /// ```
+    pub fn runs(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.runs_of(true)
+    }
+    /// Iterate over maximal ranges of consecutive disabled bits, in order.
+    ///
+    /// The last run ends at most at [`Bitset::bit_len`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0xf000_0f0e, 0x8000_0001]);
+    ///
+    /// let free: Vec<_> = bitset.zero_runs().collect();
+    /// assert_eq!(free, [0..1, 4..8, 12..28, 33..63]);
+    ///
+    /// let free: Vec<_> = Bitset([0x0000_0003, 0]).zero_runs().collect();
+    /// assert_eq!(free, [2..64]);
+    /// ```
+    pub fn zero_runs(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.runs_of(false)
+    }
+    /// Index of the first enabled bit at or after `from`, `None` if there is
+    /// none.
+    ///
+    /// This skips whole blocks of disabled bits, without creating a [`Ones`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0x0000_0011_u32, 0x0000_0100]);
+    ///
+    /// assert_eq!(bitset.next_set_bit(0), Some(0));
+    /// assert_eq!(bitset.next_set_bit(1), Some(4));
+    /// assert_eq!(bitset.next_set_bit(5), Some(40));
+    /// assert_eq!(bitset.next_set_bit(41), None);
+    /// assert_eq!(bitset.next_set_bit(1000), None);
+    /// ```
+    #[must_use]
+    pub fn next_set_bit(&self, from: usize) -> Option<usize> {
+        self.first_from(from, true)
+    }
+    /// Index of the first disabled bit in `from..end`, `None` if there is
+    /// none.
+    ///
+    /// Bits past [`Bitset::bit_len`] are disabled, so `end` bounds the search.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0xffff_fff7_u32, 0xffff_ffff]);
+    ///
+    /// assert_eq!(bitset.next_clear_bit(0, 64), Some(3));
+    /// assert_eq!(bitset.next_clear_bit(4, 64), None);
+    /// assert_eq!(bitset.next_clear_bit(4, 65), Some(64));
+    /// assert_eq!(bitset.next_clear_bit(70, 80), Some(70));
+    /// assert_eq!(bitset.next_clear_bit(3, 3), None);
+    /// ```
+    #[must_use]
+    pub fn next_clear_bit(&self, from: usize, end: usize) -> Option<usize> {
+        let clear = self.first_from(from, false);
+        let clear = clear.unwrap_or_else(|| from.max(self.bit_len()));
+        (clear < end).then_some(clear)
+    }
+    fn runs_of(&self, value: bool) -> impl Iterator<Item = Range<usize>> + '_ {
+        let mut position = 0;
+        iter::from_fn(move || {
+            let start = self.first_from(position, value)?;
+            let end = self.first_from(start, !value).unwrap_or(self.bit_len());
+            position = end;
+            Some(start..end)
+        })
+    }
+    /// Index of the first bit at or after `from` equal to `value`, `None`
+    /// if there is none within [`Bitset::bit_len`].
+    fn first_from(&self, from: usize, value: bool) -> Option<usize> {
+        let blocks = self.0.as_ref();
+        let first = from / u32::BITS64;
+        let offset = (from % u32::BITS64) as u32;
+        let flip = if value { 0 } else { u32::MAX };
+
+        let mut candidates = blocks.get(first..)?.iter().map(|block| block ^ flip);
+        let head = candidates.next()? & (u32::MAX << offset);
+        iter::once(head)
+            .chain(candidates)
+            .enumerate()
+            .find(|(_, block)| *block != 0)
+            .map(|(i, block)| (first + i) * u32::BITS64 + block.trailing_zeros() as usize)
+    }
     /// True if bit at `at` is enabled, false if out of bound or disabled.
     #[inline]
     pub fn bit(&self, at: usize) -> bool {
@@ -295,71 +1101,211 @@ impl<B: AsRef<[u32]>> Bitset<B> {
 
         block & offset == offset
     }
-    /// Returns the 32 bits in the bitset starting at `at`.
+    /// Returns the 32 bits in the bitset starting at `at`.
+    ///
+    /// # Errors
+    /// Returns an `Err` with a truncated value if `at + 32` is larger than the bitset.
+    ///
+    /// # Example
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset(&[0xf0f0_00ff, 0xfff0_000f, 0xfff0_0f0f]);
+    ///
+    /// assert_eq!(bitset.u32_at(0),  Ok(0xf0f0_00ff));
+    /// assert_eq!(bitset.u32_at(4),  Ok(0xff0f_000f));
+    /// assert_eq!(bitset.u32_at(16), Ok(0x000f_f0f0));
+    /// assert_eq!(bitset.u32_at(64), Ok(0xfff0_0f0f));
+    ///
+    /// assert_eq!(bitset.u32_at(96), Err(0));
+    /// assert_eq!(bitset.u32_at(80), Err(0xfff0));
+    /// ```
+    #[inline]
+    #[allow(clippy::similar_names)] // foo_1 is distinct from bar_0 fairly clearly
+    pub fn u32_at(&self, at: usize) -> Result<u32, u32> {
+        let block = at / u32::BITS64;
+        let offset = (at % u32::BITS64) as u32;
+
+        if offset == 0 {
+            self.0.as_ref().get(block).copied().ok_or(0)
+        } else {
+            let inset = u32::BITS - offset;
+            let msb_0 = self.0.as_ref().get(block).map_or(0, |&t| t) >> offset;
+            let lsb_1 = self.0.as_ref().get(block + 1).map_or(0, |&t| t) << inset;
+
+            let mask = safe_n_mask(inset);
+
+            let spills_out = at + 32 > self.bit_len();
+            let ctor = if spills_out { Err } else { Ok };
+            ctor((msb_0 & mask) | (lsb_1 & !mask))
+        }
+    }
+    /// Like [`Self::u32_at`], but limited to `n` bits. `n <= 32`.
+    ///
+    /// Returns `None` if `at + n` is larger than the bitset.
+    #[inline]
+    #[allow(clippy::similar_names)] // foo_1 is distinct from bar_0 fairly clearly
+    pub fn n_at(&self, n: u32, at: usize) -> Option<u32> {
+        // TODO(perf): use slice::align_to::<u64>
+        let block = at / u32::BITS64;
+        let offset = (at % u32::BITS64) as u32;
+
+        let n_mask = safe_n_mask(n);
+
+        if at + n as usize > self.bit_len() {
+            None
+        } else if offset + n <= 32 {
+            let value = *self.0.as_ref().get(block)?;
+            Some((value >> offset) & n_mask)
+        } else {
+            let inset = u32::BITS - offset;
+            let msb_0 = self.0.as_ref().get(block)? >> offset;
+            let lsb_1 = self.0.as_ref().get(block + 1)?.wrapping_shl(inset);
+
+            let mask = safe_n_mask(inset);
+
+            let value = (msb_0 & mask) | (lsb_1 & !mask);
+            Some(value & n_mask)
+        }
+    }
+    /// Returns the 64 bits in the bitset starting at `at`.
+    ///
+    /// Like [`Self::u32_at`], but reads up to three `u32` blocks.
+    ///
+    /// # Errors
+    /// Returns an `Err` with a truncated value if `at + 64` is larger than the bitset.
+    ///
+    /// # Example
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset(&[0xf0f0_00ff, 0xfff0_000f, 0xfff0_0f0f]);
+    ///
+    /// assert_eq!(bitset.u64_at(0),  Ok(0xfff0_000f_f0f0_00ff));
+    /// assert_eq!(bitset.u64_at(16), Ok(0x0f0f_fff0_000f_f0f0));
+    /// assert_eq!(bitset.u64_at(32), Ok(0xfff0_0f0f_fff0_000f));
+    ///
+    /// assert_eq!(bitset.u64_at(96), Err(0));
+    /// assert_eq!(bitset.u64_at(48), Err(0xfff0_0f0f_fff0));
+    /// ```
+    #[inline]
+    pub fn u64_at(&self, at: usize) -> Result<u64, u64> {
+        let block = at / u32::BITS64;
+        let offset = at % u32::BITS64;
+
+        let blocks = self.0.as_ref();
+        let get = |i: usize| u128::from(blocks.get(block + i).map_or(0, |&t| t));
+        let window = get(0) | get(1) << 32 | get(2) << 64;
+        let value = (window >> offset) as u64;
+
+        let spills_out = at + 64 > self.bit_len();
+        if spills_out {
+            Err(value)
+        } else {
+            Ok(value)
+        }
+    }
+    /// Like [`Self::u64_at`], but limited to `n` bits. `n <= 64`.
+    ///
+    /// Returns `None` if `at + n` is larger than the bitset.
+    ///
+    /// # Example
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset(&[0xf0f0_00ff, 0xfff0_000f, 0xfff0_0f0f]);
+    ///
+    /// assert_eq!(bitset.n64_at(40, 4), Some(0x00_ff0f_000f));
+    /// assert_eq!(bitset.n64_at(64, 32), Some(0xfff0_0f0f_fff0_000f));
+    /// assert_eq!(bitset.n64_at(40, 60), None);
+    /// ```
+    #[inline]
+    pub fn n64_at(&self, n: u32, at: usize) -> Option<u64> {
+        if at + n as usize > self.bit_len() {
+            return None;
+        }
+        let value = self.u64_at(at).unwrap_or_else(|value| value);
+        let n_mask = u64::MAX
+            .checked_shr(u64::BITS.saturating_sub(n))
+            .unwrap_or(0);
+        Some(value & n_mask)
+    }
+    /// Iterate over the enabled bits in parallel, using [`rayon`].
+    ///
+    /// Like [`Bitset::ones`], but each block is processed independently,
+    /// so the indices are not visited in order.
+    ///
+    /// # Example
+    /// ```
+    /// # use datazoo::Bitset;
+    /// use rayon::prelude::*;
+    ///
+    /// let bitset = Bitset(&[0xf0f0_00ff, 0xfff0_000f, 0xfff0_0f0f]);
+    ///
+    /// let mut parallel: Vec<u32> = bitset.par_ones().collect();
+    /// parallel.sort_unstable();
+    /// assert_eq!(parallel, bitset.ones().collect::<Vec<_>>());
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_ones(&self) -> impl rayon::iter::ParallelIterator<Item = u32> + '_ {
+        use rayon::prelude::*;
+
+        self.0
+            .as_ref()
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(i, &block)| block_ones(block, i * u32::BITS64).map(|bit| bit as u32))
+    }
+    /// How many bits are enabled in this bitset.
+    ///
+    /// Not to be confused with [`Bitset::bit_len`], the total number of bits.
+    /// This is the same as `self.ones().len()`, blocks are counted 32 bits
+    /// at a time.
+    ///
+    /// # Example
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0xf0f0_00ff_u32, 0, 0x0000_0101]);
+    ///
+    /// assert_eq!(bitset.ones_count(), 18);
+    /// assert_eq!(bitset.bit_len(), 96);
+    /// assert_eq!(bitset.ones_count(), (&bitset).into_iter().len());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn ones_count(&self) -> usize {
+        self.ones().len()
+    }
+    /// `true` if no bits are enabled, stopping at the first non-zero block.
+    ///
+    /// This is cheaper than `self.ones_count() == 0`. An empty bitset is all
+    /// zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use datazoo::Bitset;
+    /// assert!(Bitset([0_u32, 0]).is_all_zero());
+    /// assert!(Bitset([]).is_all_zero());
+    /// assert!(!Bitset([0, 0x0100_0000]).is_all_zero());
+    /// ```
+    #[must_use]
+    pub fn is_all_zero(&self) -> bool {
+        self.0.as_ref().iter().all(|block| *block == 0)
+    }
+    /// `true` if all bits up to [`Bitset::bit_len`] are enabled, stopping at
+    /// the first block that isn't `u32::MAX`.
     ///
-    /// # Errors
-    /// Returns an `Err` with a truncated value if `at + 32` is larger than the bitset.
+    /// This includes the bits of the last block, even when you consider the
+    /// bitset to be shorter than `bit_len`, see [`Bitset::with_len`].
+    /// An empty bitset is all one.
     ///
     /// # Example
     /// ```
     /// # use datazoo::Bitset;
-    /// let bitset = Bitset(&[0xf0f0_00ff, 0xfff0_000f, 0xfff0_0f0f]);
-    ///
-    /// assert_eq!(bitset.u32_at(0),  Ok(0xf0f0_00ff));
-    /// assert_eq!(bitset.u32_at(4),  Ok(0xff0f_000f));
-    /// assert_eq!(bitset.u32_at(16), Ok(0x000f_f0f0));
-    /// assert_eq!(bitset.u32_at(64), Ok(0xfff0_0f0f));
-    ///
-    /// assert_eq!(bitset.u32_at(96), Err(0));
-    /// assert_eq!(bitset.u32_at(80), Err(0xfff0));
+    /// assert!(Bitset([u32::MAX, u32::MAX]).is_all_one());
+    /// assert!(Bitset([]).is_all_one());
+    /// assert!(!Bitset([u32::MAX, 0x7fff_ffff]).is_all_one());
     /// ```
-    #[inline]
-    #[allow(clippy::similar_names)] // foo_1 is distinct from bar_0 fairly clearly
-    pub fn u32_at(&self, at: usize) -> Result<u32, u32> {
-        let block = at / u32::BITS64;
-        let offset = (at % u32::BITS64) as u32;
-
-        if offset == 0 {
-            self.0.as_ref().get(block).copied().ok_or(0)
-        } else {
-            let inset = u32::BITS - offset;
-            let msb_0 = self.0.as_ref().get(block).map_or(0, |&t| t) >> offset;
-            let lsb_1 = self.0.as_ref().get(block + 1).map_or(0, |&t| t) << inset;
-
-            let mask = safe_n_mask(inset);
-
-            let spills_out = at + 32 > self.bit_len();
-            let ctor = if spills_out { Err } else { Ok };
-            ctor((msb_0 & mask) | (lsb_1 & !mask))
-        }
-    }
-    /// Like [`Self::u32_at`], but limited to `n` bits. `n <= 32`.
-    ///
-    /// Returns `None` if `at + n` is larger than the bitset.
-    #[inline]
-    #[allow(clippy::similar_names)] // foo_1 is distinct from bar_0 fairly clearly
-    pub fn n_at(&self, n: u32, at: usize) -> Option<u32> {
-        // TODO(perf): use slice::align_to::<u64>
-        let block = at / u32::BITS64;
-        let offset = (at % u32::BITS64) as u32;
-
-        let n_mask = safe_n_mask(n);
-
-        if at + n as usize > self.bit_len() {
-            None
-        } else if offset + n <= 32 {
-            let value = *self.0.as_ref().get(block)?;
-            Some((value >> offset) & n_mask)
-        } else {
-            let inset = u32::BITS - offset;
-            let msb_0 = self.0.as_ref().get(block)? >> offset;
-            let lsb_1 = self.0.as_ref().get(block + 1)?.wrapping_shl(inset);
-
-            let mask = safe_n_mask(inset);
-
-            let value = (msb_0 & mask) | (lsb_1 & !mask);
-            Some(value & n_mask)
-        }
+    #[must_use]
+    pub fn is_all_one(&self) -> bool {
+        self.0.as_ref().iter().all(|block| *block == u32::MAX)
     }
     /// Same as [`self.ones_in_range(..)`].
     ///
@@ -373,23 +1319,83 @@ impl<B: AsRef<[u32]>> Bitset<B> {
     ///
     /// [`self.ones_in_range(..)`]: Bitset::ones_in_range
     #[inline]
-    pub fn ones(&self) -> Ones {
+    pub fn ones(&self) -> Ones<'_> {
         let blocks = self.0.as_ref();
         let (bitset, remaining_blocks) = blocks.split_first().map_or((0, blocks), |(b, r)| (*b, r));
         Ones { block_idx: 0, crop: 0, bitset, remaining_blocks }
     }
+    /// The `len` first bits of this bitset, where `true` is enabled.
+    ///
+    /// Item `n` of the returned `Vec` is bit `n`, bits past
+    /// [`Bitset::bit_len`] are `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0b0110_u32]);
+    ///
+    /// assert_eq!(bitset.to_bools(4), [false, true, true, false]);
+    /// assert_eq!(bitset.to_bools(34).len(), 34);
+    /// ```
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_bools(&self, len: usize) -> Vec<bool> {
+        (0..len).map(|i| self.bit(i)).collect()
+    }
+    /// Restrict this bitset to its `len` first bits.
+    ///
+    /// [`Bitset::bit_len`] is always a multiple of 32. The returned [`WithLen`]
+    /// ignores bits at or past `len` when iterating, so that trailing bits
+    /// of the last block are not part of the set.
+    ///
+    /// # Panics
+    /// If `len` is larger than [`Bitset::bit_len`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0x8000_0001_u32, 0xffff_0003]).with_len(50);
+    ///
+    /// assert_eq!(bitset.logical_len(), 50);
+    /// assert_eq!(bitset.bitset().bit_len(), 64);
+    ///
+    /// assert_eq!(bitset.ones().collect::<Vec<_>>(), [0, 31, 32, 33, 48, 49]);
+    /// assert_eq!((&bitset).into_iter().count(), 6);
+    /// assert!(bitset.bit(49));
+    /// assert!(!bitset.bit(50));
+    /// assert!(bitset.bitset().bit(50));
+    /// ```
+    #[must_use]
+    pub fn with_len(self, len: usize) -> WithLen<B> {
+        assert!(
+            len <= self.bit_len(),
+            "Logical length {len} larger than bitset length {}",
+            self.bit_len()
+        );
+        WithLen { bitset: self, len }
+    }
+    /// A cursor reading consecutive fields of this bitset, starting at bit 0.
+    ///
+    /// See [`BitReader`] for details.
+    #[inline]
+    #[must_use]
+    pub fn reader(&self) -> BitReader<'_> {
+        BitReader { bitset: Bitset(self.0.as_ref()), position: 0 }
+    }
     /// Get an iterator over the index of enabled bits within provided `range`.
     #[inline]
-    pub fn ones_in_range(&self, range: impl RangeBounds<usize>) -> Ones {
+    pub fn ones_in_range(&self, range: impl RangeBounds<usize>) -> Ones<'_> {
         let start = match range.start_bound() {
-            std::ops::Bound::Included(start) => *start,
-            std::ops::Bound::Excluded(start) => *start + 1,
-            std::ops::Bound::Unbounded => 0,
+            core::ops::Bound::Included(start) => *start,
+            core::ops::Bound::Excluded(start) => *start + 1,
+            core::ops::Bound::Unbounded => 0,
         };
         let end = match range.end_bound() {
-            std::ops::Bound::Included(end) => *end + 1,
-            std::ops::Bound::Excluded(end) => *end,
-            std::ops::Bound::Unbounded => self.bit_len(),
+            core::ops::Bound::Included(end) => *end + 1,
+            core::ops::Bound::Excluded(end) => *end,
+            core::ops::Bound::Unbounded => self.bit_len(),
         };
 
         // the offset to "crop" the bits at the edges of the [u32]
@@ -420,9 +1426,177 @@ impl<B: AsRef<[u32]>> Bitset<B> {
             remaining_blocks,
         }
     }
+    /// `true` if `self` and `other` have the same enabled bits.
+    ///
+    /// Unlike `==`, this ignores trailing zero blocks, so bitsets of different
+    /// length and storage can compare equal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let short = Bitset([0b1]);
+    /// let long = Bitset(vec![0b1, 0, 0]);
+    ///
+    /// assert!(short.semantic_eq(&long));
+    /// assert!(long.semantic_eq(&short));
+    /// assert!(!long.semantic_eq(&Bitset([0b1, 0, 0b10])));
+    /// ```
+    #[must_use]
+    pub fn semantic_eq(&self, other: &Bitset<impl AsRef<[u32]>>) -> bool {
+        let (this, other) = (self.0.as_ref(), other.0.as_ref());
+        let min_len = this.len().min(other.len());
+        let largest = if this.len() == min_len { other } else { this };
+
+        let common_identical = this[..min_len] == other[..min_len];
+        let no_more = largest[min_len..].iter().all(|v| *v == 0);
+        common_identical && no_more
+    }
+    /// Iterate over the indices of bits that differ between `self` and
+    /// `other`, in ascending order.
+    ///
+    /// This is `self ^ other`, without allocating. The shorter bitset is
+    /// treated as if it was extended with zeros.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let previous = Bitset([0b1011_u32, 0]);
+    /// let mut current = Bitset([0b0110, 0b1, 0b1]);
+    ///
+    /// let changed: Vec<_> = previous.diff_indices(&current).collect();
+    /// assert_eq!(changed, [0, 2, 3, 32, 64]);
+    ///
+    /// current.xor_with(&previous);
+    /// assert_eq!(current.ones().collect::<Vec<_>>(), [0, 2, 3, 32, 64]);
+    /// ```
+    pub fn diff_indices<'a>(
+        &'a self,
+        other: &'a Bitset<impl AsRef<[u32]>>,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let blocks = zip_blocks(self.0.as_ref(), other.0.as_ref());
+        blocks
+            .enumerate()
+            .flat_map(|(i, (this, other))| block_ones(this ^ other, i * u32::BITS64))
+    }
+    /// The number of bits that differ between `self` and `other`, the
+    /// [Hamming distance].
+    ///
+    /// The shorter bitset is treated as if it was extended with zeros.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0b1011]);
+    ///
+    /// assert_eq!(bitset.hamming_distance(&Bitset([0b0110])), 3);
+    /// assert_eq!(bitset.hamming_distance(&Bitset(vec![0b1011, 0b11])), 2);
+    /// ```
+    ///
+    /// [Hamming distance]: https://en.wikipedia.org/wiki/Hamming_distance
+    #[must_use]
+    pub fn hamming_distance(&self, other: &Bitset<impl AsRef<[u32]>>) -> usize {
+        zip_blocks(self.0.as_ref(), other.0.as_ref())
+            .map(|(this, other)| (this ^ other).count_ones() as usize)
+            .sum()
+    }
+    /// The number of bits enabled in both `self` and `other`, and the number of
+    /// bits enabled in either, in a single pass.
+    ///
+    /// This is the numerator and denominator of the [Jaccard index] of `self`
+    /// and `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let bitset = Bitset([0b1011]);
+    ///
+    /// assert_eq!(bitset.jaccard_counts(&Bitset([0b0110, 0b1])), (1, 5));
+    /// assert_eq!(Bitset([]).jaccard_counts(&Bitset([0])), (0, 0));
+    /// ```
+    ///
+    /// [Jaccard index]: https://en.wikipedia.org/wiki/Jaccard_index
+    #[must_use]
+    pub fn jaccard_counts(&self, other: &Bitset<impl AsRef<[u32]>>) -> (usize, usize) {
+        let count = |block: u32| block.count_ones() as usize;
+        zip_blocks(self.0.as_ref(), other.0.as_ref()).fold((0, 0), |(and, or), (this, other)| {
+            (and + count(this & other), or + count(this | other))
+        })
+    }
+    /// `true` if all bits enabled in `self` are also enabled in `other`.
+    ///
+    /// The shorter bitset is treated as if it was extended with zeros.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::Bitset;
+    /// let small = Bitset([0b0010]);
+    /// let large = Bitset(vec![0b1010, 0b1]);
+    ///
+    /// assert!(small.is_subset(&large));
+    /// assert!(!large.is_subset(&small));
+    /// assert!(Bitset([0b10, 0, 0]).is_subset(&small));
+    /// ```
+    #[must_use]
+    pub fn is_subset(&self, other: &Bitset<impl AsRef<[u32]>>) -> bool {
+        zip_blocks(self.0.as_ref(), other.0.as_ref()).all(|(this, other)| this & !other == 0)
+    }
+    /// `true` if all bits enabled in `other` are also enabled in `self`.
+    ///
+    /// This is `other.is_subset(self)`.
+    #[must_use]
+    pub fn is_superset(&self, other: &Bitset<impl AsRef<[u32]>>) -> bool {
+        other.is_subset(self)
+    }
+    /// Compare `self` with other bitsets by set inclusion, see [`SubsetOrd`].
+    #[inline]
+    #[must_use]
+    pub const fn subset_ord(self) -> SubsetOrd<B> {
+        SubsetOrd(self)
+    }
+}
+/// Iterate over the enabled bits of `block`, as indices offset by `base`.
+fn block_ones(mut block: u32, base: usize) -> impl Iterator<Item = usize> {
+    iter::from_fn(move || {
+        let bit = block.trailing_zeros();
+        block &= block.wrapping_sub(1);
+        (bit != u32::BITS).then_some(base + bit as usize)
+    })
+}
+/// Iterate over pairs of blocks of `this` and `other`, the shorter one is
+/// extended with zeros.
+fn zip_blocks<'a>(this: &'a [u32], other: &'a [u32]) -> impl Iterator<Item = (u32, u32)> + 'a {
+    let get = |blocks: &[u32], i: usize| blocks.get(i).copied().unwrap_or(0);
+    (0..this.len().max(other.len())).map(move |i| (get(this, i), get(other, i)))
 }
+/// Prints blocks in hexadecimal, or the indices of enabled bits with the
+/// alternate flag (`{:#?}`).
+///
+/// # Example
+///
+/// ```
+/// # use datazoo::Bitset;
+/// let bitset = Bitset([0x0002_0008, 0, 0x0000_0001]);
+///
+/// assert_eq!(format!("{bitset:?}"), "[00020008_00000000_00000001]");
+/// assert_eq!(format!("{bitset:#?}"), "{3, 17, 64}");
+/// ```
 impl<B: AsRef<[u32]>> fmt::Debug for Bitset<B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{{")?;
+            for (i, bit) in self.ones().enumerate() {
+                if i != 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{bit}")?;
+            }
+            return write!(f, "}}");
+        }
         write!(f, "[")?;
         for (i, block) in self.0.as_ref().iter().enumerate() {
             if i != 0 {
@@ -441,6 +1615,7 @@ impl<'a, B: AsRef<[u32]>> IntoIterator for &'a Bitset<B> {
         self.ones_in_range(0..self.bit_len())
     }
 }
+#[cfg(feature = "alloc")]
 impl Extend<u32> for Bitset<Vec<u32>> {
     #[inline]
     fn extend<T: IntoIterator<Item = u32>>(&mut self, iter: T) {
@@ -448,6 +1623,7 @@ impl Extend<u32> for Bitset<Vec<u32>> {
             .for_each(|bit| self.enable_bit_extending(bit as usize));
     }
 }
+#[cfg(feature = "alloc")]
 impl Extend<usize> for Bitset<Vec<u32>> {
     #[inline]
     fn extend<T: IntoIterator<Item = usize>>(&mut self, iter: T) {
@@ -455,6 +1631,7 @@ impl Extend<usize> for Bitset<Vec<u32>> {
             .for_each(|bit| self.enable_bit_extending(bit));
     }
 }
+#[cfg(feature = "alloc")]
 impl Extend<u32> for Bitset<Box<[u32]>> {
     /// Add the iterator items to the `Bitset`, will **not** increase the
     /// bitset size.
@@ -465,6 +1642,7 @@ impl Extend<u32> for Bitset<Box<[u32]>> {
         });
     }
 }
+#[cfg(feature = "alloc")]
 impl Extend<usize> for Bitset<Box<[u32]>> {
     /// Add the iterator items to the `Bitset`, will **not** increase the
     /// bitset size.
@@ -475,12 +1653,14 @@ impl Extend<usize> for Bitset<Box<[u32]>> {
         });
     }
 }
+#[cfg(feature = "alloc")]
 impl FromIterator<u32> for Bitset<Box<[u32]>> {
     fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
         let acc: Bitset<Vec<_>> = iter.into_iter().collect();
         Bitset(acc.0.into_boxed_slice())
     }
 }
+#[cfg(feature = "alloc")]
 impl FromIterator<u32> for Bitset<Vec<u32>> {
     fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
         let iter = iter.into_iter();
@@ -489,12 +1669,50 @@ impl FromIterator<u32> for Bitset<Vec<u32>> {
         acc
     }
 }
+#[cfg(feature = "alloc")]
+impl FromIterator<bool> for Bitset<Box<[u32]>> {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let acc: Bitset<Vec<_>> = iter.into_iter().collect();
+        Bitset(acc.0.into_boxed_slice())
+    }
+}
+/// The `n`th item of the iterator is bit `n`. The bitset has as many bits
+/// as there are items, rounded up to a multiple of 32.
+///
+/// # Example
+///
+/// ```
+/// # use datazoo::Bitset;
+/// let bitset: Bitset<Vec<u32>> = (0..40).map(|i| i % 3 == 0).collect();
+///
+/// assert_eq!(bitset.bit_len(), 64);
+/// assert_eq!(bitset.ones().take(4).collect::<Vec<_>>(), [0, 3, 6, 9]);
+/// assert_eq!(bitset.ones().last(), Some(39));
+/// ```
+#[cfg(feature = "alloc")]
+impl FromIterator<bool> for Bitset<Vec<u32>> {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let mut blocks = Vec::new();
+        for (i, bit) in iter.into_iter().enumerate() {
+            let offset = i % u32::BITS64;
+            if offset == 0 {
+                blocks.push(0);
+            }
+            if let (true, Some(block)) = (bit, blocks.last_mut()) {
+                *block |= 1 << offset;
+            }
+        }
+        Bitset(blocks)
+    }
+}
+#[cfg(feature = "alloc")]
 impl FromIterator<usize> for Bitset<Box<[u32]>> {
     fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
         let acc: Bitset<Vec<_>> = iter.into_iter().collect();
         Bitset(acc.0.into_boxed_slice())
     }
 }
+#[cfg(feature = "alloc")]
 impl FromIterator<usize> for Bitset<Vec<u32>> {
     fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
         let iter = iter.into_iter();
@@ -504,6 +1722,161 @@ impl FromIterator<usize> for Bitset<Vec<u32>> {
     }
 }
 
+/// A [`Bitset`] with a logical length not necessarily a multiple of 32,
+/// see [`Bitset::with_len`].
+///
+/// Bits at or past [`WithLen::logical_len`] are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithLen<B: AsRef<[u32]>> {
+    bitset: Bitset<B>,
+    len: usize,
+}
+impl<B: AsRef<[u32]>> WithLen<B> {
+    /// How many bits are part of this bitset.
+    #[inline]
+    #[must_use]
+    pub const fn logical_len(&self) -> usize {
+        self.len
+    }
+    /// The underlying bitset, including bits past [`WithLen::logical_len`].
+    #[inline]
+    #[must_use]
+    pub const fn bitset(&self) -> &Bitset<B> {
+        &self.bitset
+    }
+    /// Get back the underlying bitset.
+    #[inline]
+    #[must_use]
+    pub fn into_bitset(self) -> Bitset<B> {
+        self.bitset
+    }
+    /// True if bit at `at` is enabled, false if disabled or at or past
+    /// [`WithLen::logical_len`].
+    #[inline]
+    #[must_use]
+    pub fn bit(&self, at: usize) -> bool {
+        at < self.len && self.bitset.bit(at)
+    }
+    /// Iterate over the index of enabled bits smaller than [`WithLen::logical_len`].
+    #[inline]
+    pub fn ones(&self) -> Ones<'_> {
+        self.bitset.ones_in_range(..self.len)
+    }
+}
+impl<'a, B: AsRef<[u32]>> IntoIterator for &'a WithLen<B> {
+    type Item = u32;
+    type IntoIter = Ones<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.ones()
+    }
+}
+
+/// A [`Bitset`] ordered by set inclusion, see [`Bitset::subset_ord`].
+///
+/// `a <= b` if `a` is a subset of `b`. This is a **partial** order:
+/// `partial_cmp` returns `None` when neither bitset is a subset of the other,
+/// and `a < b`, `a > b` and `a == b` can all be `false` at the same time.
+///
+/// `==` means both bitsets have the same enabled bits, like
+/// [`Bitset::semantic_eq`]. Trailing zero blocks are ignored.
+///
+/// # Example
+///
+/// ```
+/// # use datazoo::bitset::{Bitset, SubsetOrd};
+/// use std::cmp::Ordering;
+///
+/// let small = SubsetOrd(Bitset([0b0010]));
+/// let large = SubsetOrd(Bitset([0b1010, 0]));
+/// let other = SubsetOrd(Bitset([0b0101]));
+///
+/// assert!(small < large);
+/// assert!(small <= SubsetOrd(Bitset([0b0010, 0, 0])));
+/// assert_eq!(small, SubsetOrd(Bitset([0b0010, 0, 0])));
+///
+/// assert_eq!(small.partial_cmp(&other), None);
+/// assert!(!(small < other) && !(small > other) && small != other);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SubsetOrd<B: AsRef<[u32]>>(pub Bitset<B>);
+
+impl<B: AsRef<[u32]>, C: AsRef<[u32]>> PartialEq<SubsetOrd<C>> for SubsetOrd<B> {
+    #[inline]
+    fn eq(&self, other: &SubsetOrd<C>) -> bool {
+        self.0.semantic_eq(&other.0)
+    }
+}
+impl<B: AsRef<[u32]>> Eq for SubsetOrd<B> {}
+
+impl<B: AsRef<[u32]>, C: AsRef<[u32]>> PartialOrd<SubsetOrd<C>> for SubsetOrd<B> {
+    fn partial_cmp(&self, other: &SubsetOrd<C>) -> Option<Ordering> {
+        let (mut subset, mut superset) = (true, true);
+        for (this, other) in zip_blocks(self.0 .0.as_ref(), other.0 .0.as_ref()) {
+            subset &= this & !other == 0;
+            superset &= other & !this == 0;
+            if !subset && !superset {
+                return None;
+            }
+        }
+        match (subset, superset) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Cursor reading consecutive fields of variable width from a [`Bitset`],
+/// see [`Bitset::reader`].
+///
+/// # Example
+///
+/// ```
+/// # use datazoo::Bitset;
+/// let bitset = Bitset([0x8765_4321_u32, 0xff]);
+/// let mut reader = bitset.reader();
+///
+/// assert_eq!(reader.read_bits(4), Some(0x1));
+/// assert_eq!(reader.read_bits(8), Some(0x32));
+/// reader.skip(4);
+/// assert_eq!(reader.position(), 16);
+/// assert_eq!(reader.read_bits(24), Some(0xff_8765));
+///
+/// // Not enough bits left, the cursor doesn't move.
+/// assert_eq!(reader.read_bits(32), None);
+/// assert_eq!(reader.read_bits(24), Some(0));
+/// assert_eq!(reader.read_bits(1), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitReader<'a> {
+    bitset: Bitset<&'a [u32]>,
+    position: usize,
+}
+impl BitReader<'_> {
+    /// Read the next `n` bits as with [`Bitset::n_at`], moving the cursor
+    /// past them. `n <= 32`.
+    ///
+    /// Returns `None` and doesn't move the cursor if there are less than `n`
+    /// bits left.
+    pub fn read_bits(&mut self, n: u32) -> Option<u32> {
+        let value = self.bitset.n_at(n, self.position)?;
+        self.position += n as usize;
+        Some(value)
+    }
+    /// Move the cursor `n` bits forward, without reading them.
+    #[inline]
+    pub fn skip(&mut self, n: usize) {
+        self.position = self.position.saturating_add(n);
+    }
+    /// The index of the next bit to read.
+    #[inline]
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+}
+
 // TODO(perf): consider swapping block_idx, crop: u16
 // or even a compact u26|u6 because `crop` can at most be `32`
 /// Iterator over the enables bits of the subset of a [`Bitset`].
@@ -523,22 +1896,55 @@ impl Iterator for Ones<'_> {
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         while self.bitset == 0 {
-            let Some((&bitset, remaining_blocks)) = self.remaining_blocks.split_first() else {
-                return None;
-            };
-            self.bitset = bitset;
-            self.remaining_blocks = remaining_blocks;
-
-            if self.remaining_blocks.is_empty() && self.crop != 0 {
-                self.bitset &= (1 << self.crop) - 1;
-            }
-            self.block_idx += 1;
+            self.next_block()?;
         }
         let t = self.bitset & 0_u32.wrapping_sub(self.bitset);
         let r = self.bitset.trailing_zeros();
         self.bitset ^= t;
         Some(self.block_idx * u32::BITS + r)
     }
+    /// Skips whole blocks based on their popcount, rather than iterating
+    /// over each individual enabled bit.
+    #[inline]
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        loop {
+            let ones = self.bitset.count_ones() as usize;
+            if n < ones {
+                // Disable the `n` lowest enabled bits
+                for _ in 0..n {
+                    self.bitset &= self.bitset - 1;
+                }
+                return self.next();
+            }
+            n -= ones;
+            self.bitset = 0;
+            self.next_block()?;
+        }
+    }
+    /// Sums the popcount of remaining blocks, rather than iterating over each
+    /// individual enabled bit.
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+    /// Jumps to the last non-zero block, rather than iterating over each
+    /// individual enabled bit.
+    #[inline]
+    fn last(mut self) -> Option<Self::Item> {
+        let crop_mask = self.crop_mask();
+        let last_block = |(i, block): (usize, &u32)| {
+            let is_last = i + 1 == self.remaining_blocks.len();
+            let block = if is_last { block & crop_mask } else { *block };
+            (block != 0).then_some((i, block))
+        };
+        let blocks = self.remaining_blocks.iter().enumerate();
+        if let Some((i, block)) = blocks.rev().find_map(last_block) {
+            self.block_idx += i as u32 + 1;
+            self.bitset = block;
+        }
+        let highest = self.bitset.checked_ilog2()?;
+        Some(self.block_idx * u32::BITS + highest)
+    }
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
         let bitset_ones = self.bitset.count_ones();
@@ -547,7 +1953,8 @@ impl Iterator for Ones<'_> {
             return (bitset_ones as usize, Some(bitset_ones as usize));
         };
         let ones: u32 = slice.iter().map(|b| b.count_ones()).sum();
-        let trailing_bits = last & !((1 << self.crop) - 1);
+        let crop_mask = self.crop_mask();
+        let trailing_bits = last & crop_mask;
         let trailing_bits = trailing_bits.count_ones();
 
         let exact_size = (bitset_ones + ones + trailing_bits) as usize;
@@ -555,7 +1962,32 @@ impl Iterator for Ones<'_> {
     }
 }
 impl ExactSizeIterator for Ones<'_> {}
+impl Ones<'_> {
+    /// Mask of the bits to keep in the last block.
+    #[inline]
+    const fn crop_mask(&self) -> u32 {
+        if self.crop == 0 {
+            u32::MAX
+        } else {
+            safe_n_mask(self.crop)
+        }
+    }
+    /// Replace `bitset` with the next block, `None` if there is no more blocks.
+    #[inline]
+    fn next_block(&mut self) -> Option<()> {
+        let (&bitset, remaining_blocks) = self.remaining_blocks.split_first()?;
+        self.bitset = bitset;
+        self.remaining_blocks = remaining_blocks;
+
+        if self.remaining_blocks.is_empty() && self.crop != 0 {
+            self.bitset &= (1 << self.crop) - 1;
+        }
+        self.block_idx += 1;
+        Some(())
+    }
+}
 
+#[cfg(feature = "std")]
 impl SortedByItem for Ones<'_> {}
 
 impl Ones<'_> {
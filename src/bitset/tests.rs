@@ -239,3 +239,349 @@ fn exact_size_len() {
     let actual = blocks.ones_in_range(23..23);
     assert_eq!(0, actual.len());
 }
+#[test]
+fn nth_matches_next() {
+    let blocks = Bitset([0xf0f0_00ff, 0, 0, 0x8000_0001, 0xfff0_0f0f, 0, 0xffff_ffff]);
+    let ranges = [
+        0..224,
+        0..0,
+        5..5,
+        3..200,
+        24..76,
+        96..129,
+        100..224,
+        31..32,
+        127..128,
+    ];
+    for range in ranges {
+        let expected: Vec<_> = blocks.ones_in_range(range.clone()).collect();
+        for n in 0..expected.len() + 3 {
+            let mut ones = blocks.ones_in_range(range.clone());
+            assert_eq!(ones.nth(n), expected.get(n).copied(), "{range:?} nth({n})");
+
+            let rest: Vec<_> = ones.collect();
+            let expected_rest = expected.get(n + 1..).unwrap_or(&[]);
+            assert_eq!(rest, expected_rest, "{range:?} after nth({n})");
+        }
+        // Chained `nth` calls
+        let mut ones = blocks.ones_in_range(range.clone());
+        let chained: Vec<_> = iter::from_fn(|| ones.nth(2)).collect();
+        let expected: Vec<_> = expected.into_iter().skip(2).step_by(3).collect();
+        assert_eq!(chained, expected, "{range:?} step_by(3)");
+    }
+}
+#[test]
+fn n_at_matches_bits() {
+    let blocks = Bitset([0xf0f0_00ff, 0x1234_5678, 0xfff0_0f0f, 0x8000_0001]);
+    for n in 0..=32 {
+        for at in 0..blocks.bit_len() + 2 {
+            let expected = (at + n as usize <= blocks.bit_len()).then(|| {
+                (0..n as usize).fold(0_u32, |acc, i| acc | u32::from(blocks.bit(at + i)) << i)
+            });
+            // `n_at` with n = 0 is `None` past the last block.
+            let expected = expected.filter(|_| at < blocks.bit_len());
+            assert_eq!(blocks.n_at(n, at), expected, "n_at({n}, {at})");
+        }
+    }
+}
+#[test]
+fn n64_at_matches_bits() {
+    let blocks = Bitset([0xf0f0_00ff, 0x1234_5678, 0xfff0_0f0f, 0x8000_0001]);
+    for n in 0..=64 {
+        for at in 0..blocks.bit_len() + 2 {
+            let expected = (at + n as usize <= blocks.bit_len()).then(|| {
+                (0..n as usize).fold(0_u64, |acc, i| acc | u64::from(blocks.bit(at + i)) << i)
+            });
+            assert_eq!(blocks.n64_at(n, at), expected, "n64_at({n}, {at})");
+        }
+    }
+}
+#[test]
+fn rotate_matches_bits() {
+    let original = Bitset([
+        0xf0f0_00ff,
+        0x1234_5678,
+        0xfff0_0f0f,
+        0x8000_0001,
+        0x0000_0003,
+    ]);
+    let bit_len = original.bit_len();
+    for n in 0..bit_len * 2 + 3 {
+        let mut left = original;
+        left.rotate_left(n);
+        let mut right = original;
+        right.rotate_right(n);
+
+        for i in 0..bit_len {
+            assert_eq!(
+                left.bit((i + n) % bit_len),
+                original.bit(i),
+                "rotate_left({n}) bit {i}"
+            );
+            assert_eq!(
+                right.bit(i),
+                original.bit((i + n) % bit_len),
+                "rotate_right({n}) bit {i}"
+            );
+        }
+        assert_eq!(left.ones().len(), original.ones().len());
+    }
+    let mut single = Bitset([0x8000_0000]);
+    single.rotate_left(1);
+    assert_eq!(single.0, [1]);
+    let mut empty = Bitset([]);
+    empty.rotate_left(3);
+    empty.rotate_right(3);
+}
+#[test]
+fn shift_matches_bits() {
+    let original = Bitset([
+        0xf0f0_00ff,
+        0x1234_5678,
+        0xfff0_0f0f,
+        0x8000_0001,
+        0x0000_0003,
+    ]);
+    let bit_len = original.bit_len();
+    for n in 0..bit_len + 40 {
+        let mut left = original;
+        left.shift_left(n);
+        let mut right = original;
+        right.shift_right(n);
+
+        for i in 0..bit_len {
+            let expected_left = i >= n && original.bit(i - n);
+            assert_eq!(left.bit(i), expected_left, "shift_left({n}) bit {i}");
+            assert_eq!(
+                right.bit(i),
+                original.bit(i + n),
+                "shift_right({n}) bit {i}"
+            );
+        }
+    }
+}
+#[test]
+fn enable_all_matches_enable_bit() {
+    let indices = [0, 1, 31, 32, 33, 200, 64, 5, 5, 95, 96];
+    let mut expected = Bitset(vec![]);
+    for i in indices {
+        expected.enable_bit_extending(i as usize);
+    }
+    let mut all = Bitset(vec![]);
+    all.enable_all(&indices);
+    assert_eq!(all, expected);
+
+    let mut in_bounds = Bitset([0_u32; 3]);
+    in_bounds.enable_all_in_bounds(&indices);
+    let expected: Vec<_> = indices.iter().copied().filter(|i| *i < 96).collect();
+    assert!(in_bounds
+        .ones()
+        .eq(Bitset::<Vec<u32>>::from_iter(expected).ones()));
+}
+#[test]
+fn ones_in_range_len() {
+    let bitset = Bitset([0xf0f0_00ff, 0x1234_5678, 0xfff0_0f0f, 0x8000_0001]);
+    for start in 0..bitset.bit_len() {
+        for end in start..=bitset.bit_len() {
+            let ones = bitset.ones_in_range(start..end);
+            let expected = (start..end).filter(|i| bitset.bit(*i)).count();
+            assert_eq!(ones.len(), expected, "ones_in_range({start}..{end})");
+        }
+    }
+}
+#[test]
+fn ones_count_last() {
+    let bitset = Bitset([0xf0f0_00ff, 0, 0x1234_5678, 0, 0x8000_0001, 0]);
+    for start in 0..bitset.bit_len() {
+        for end in start..=bitset.bit_len() {
+            let ones = || bitset.ones_in_range(start..end);
+            let name = format!("ones_in_range({start}..{end})");
+            assert_eq!(ones().count(), ones().fold(0, |acc, _| acc + 1), "{name}");
+            assert_eq!(ones().last(), ones().fold(None, |_, i| Some(i)), "{name}");
+
+            let mut partial = ones();
+            partial.next();
+            let expected = ones().skip(1).fold(None, |_, i| Some(i));
+            assert_eq!(partial.last(), expected, "{name}");
+        }
+    }
+}
+#[test]
+fn from_sorted_iter_matches_collect() {
+    let bitsets: [&[u32]; 5] = [
+        &[],
+        &[0, 0],
+        &[0b1],
+        &[0, 0, 0x8000_0000],
+        &[0xf0f0_00ff, 0, 0, 0x1234_5678, 0x1],
+    ];
+    for blocks in bitsets {
+        let bitset = Bitset(blocks);
+        let collected: Bitset<Box<[u32]>> = bitset.ones().collect();
+        assert_eq!(
+            Bitset::from_sorted_iter(bitset.ones()),
+            collected,
+            "{blocks:?}"
+        );
+    }
+}
+#[test]
+fn runs_match_bits() {
+    let bitsets: [&[u32]; 6] = [
+        &[],
+        &[0, 0],
+        &[u32::MAX, u32::MAX],
+        &[0x8000_0000, 0x0000_0001],
+        &[0xf0f0_00ff, 0, u32::MAX, 0x1234_5678],
+        &[0x0000_0001, 0x8000_0000],
+    ];
+    for blocks in bitsets {
+        let bitset = Bitset(blocks);
+        let runs: Vec<_> = bitset.runs().collect();
+        let zero_runs: Vec<_> = bitset.zero_runs().collect();
+
+        let mut expected = Vec::new();
+        let mut expected_zero = Vec::new();
+        for i in 0..bitset.bit_len() {
+            let target = if bitset.bit(i) { &mut expected } else { &mut expected_zero };
+            match target.last_mut() {
+                Some(Range { end, .. }) if *end == i => *end += 1,
+                _ => target.push(i..i + 1),
+            }
+        }
+        assert_eq!(runs, expected, "{blocks:?}");
+        assert_eq!(zero_runs, expected_zero, "{blocks:?}");
+    }
+}
+#[test]
+fn next_set_and_clear_bit() {
+    let bitsets: [&[u32]; 6] = [
+        &[],
+        &[0, 0],
+        &[u32::MAX, u32::MAX],
+        &[0x8000_0000, 0x0000_0001],
+        &[0xf0f0_00ff, 0, u32::MAX, 0x1234_5678],
+        &[0x0000_0001, 0x8000_0000, 0xffff_fffe],
+    ];
+    for blocks in bitsets {
+        let bitset = Bitset(blocks);
+        let end = bitset.bit_len() + 3;
+        for from in 0..end + 3 {
+            let next_set = (from..end).find(|i| bitset.bit(*i));
+            let next_clear = (from..end).find(|i| !bitset.bit(*i));
+            assert_eq!(bitset.next_set_bit(from), next_set, "{blocks:?} {from}");
+            assert_eq!(
+                bitset.next_clear_bit(from, end),
+                next_clear,
+                "{blocks:?} {from}"
+            );
+        }
+    }
+}
+#[test]
+fn subset_ord_matches_ones() {
+    use std::collections::BTreeSet;
+
+    let bitsets: [&[u32]; 7] = [
+        &[],
+        &[0, 0],
+        &[0b0010],
+        &[0b1010, 0],
+        &[0b0101],
+        &[0b1010, 0b1],
+        &[u32::MAX, u32::MAX],
+    ];
+    for left in bitsets {
+        for right in bitsets {
+            let (left, right) = (Bitset(left), Bitset(right));
+            let left_set: BTreeSet<_> = left.ones().collect();
+            let right_set: BTreeSet<_> = right.ones().collect();
+
+            let expected = match (
+                left_set.is_subset(&right_set),
+                right_set.is_subset(&left_set),
+            ) {
+                (true, true) => Some(Ordering::Equal),
+                (true, false) => Some(Ordering::Less),
+                (false, true) => Some(Ordering::Greater),
+                (false, false) => None,
+            };
+            assert_eq!(left.is_subset(&right), left_set.is_subset(&right_set));
+            assert_eq!(left.is_superset(&right), left_set.is_superset(&right_set));
+            assert_eq!(
+                left.subset_ord().partial_cmp(&right.subset_ord()),
+                expected,
+                "{left:?} {right:?}"
+            );
+        }
+    }
+}
+#[test]
+fn words64_split() {
+    #[repr(C, align(8))]
+    struct Aligned([u32; 9]);
+
+    let blocks = Aligned(std::array::from_fn(|i| {
+        (i as u32).wrapping_mul(0x9e37_79b9)
+    }));
+    // (head, body, tail) lengths, blocks[0] is on an 8 bytes boundary.
+    let expected = [(0, 4, 1), (1, 3, 1), (0, 3, 1), (1, 2, 1)];
+    for (start, expected) in expected.into_iter().enumerate() {
+        let bitset = Bitset(&blocks.0[start..]);
+        let (head, body, tail) = bitset.words64();
+        assert_eq!(
+            (head.len(), body.len(), tail.len()),
+            expected,
+            "start {start}"
+        );
+
+        let body_blocks = body.iter().flat_map(|word| {
+            let bytes = word.to_ne_bytes();
+            let block = |i: usize| u32::from_ne_bytes(bytes[i..i + 4].try_into().unwrap());
+            [block(0), block(4)]
+        });
+        let reassembled: Vec<_> = head
+            .iter()
+            .copied()
+            .chain(body_blocks)
+            .chain(tail.iter().copied())
+            .collect();
+        assert_eq!(reassembled, bitset.blocks(), "start {start}");
+
+        #[cfg(target_endian = "little")]
+        for (i, word) in body.iter().enumerate() {
+            let at = (head.len() + i * 2) * 32;
+            assert_eq!(
+                Some(*word),
+                bitset.n64_at(64, at),
+                "start {start}, word {i}"
+            );
+        }
+    }
+}
+#[test]
+fn all_zero_all_one() {
+    let bitsets: [(&[u32], bool, bool); 7] = [
+        (&[], true, true),
+        (&[0, 0, 0], true, false),
+        (&[u32::MAX, u32::MAX], false, true),
+        (&[0, 0, 1], false, false),
+        (&[u32::MAX, 0xffff_fffe], false, false),
+        (&[0xf0f0_00ff, 0, u32::MAX], false, false),
+        (&[0x8000_0000], false, false),
+    ];
+    for (blocks, all_zero, all_one) in bitsets {
+        let bitset = Bitset(blocks);
+        assert_eq!(bitset.is_all_zero(), all_zero, "{blocks:?}");
+        assert_eq!(bitset.is_all_one(), all_one, "{blocks:?}");
+        assert_eq!(bitset.is_all_zero(), bitset.ones_count() == 0);
+        assert_eq!(bitset.is_all_one(), bitset.ones_count() == bitset.bit_len());
+    }
+}
+
+#[test]
+#[should_panic(expected = "bit index overflows usize")]
+fn enable_bit_exact_overflow() {
+    let mut bitset = Bitset(Vec::new());
+    bitset.enable_bit_exact(usize::MAX);
+}
@@ -1,7 +1,7 @@
 //! A bitset similar to [`BitMatrix`][super::BitMatrix],
 //! indexed by [`EnumSetType`].
 
-use std::{any, fmt, marker::PhantomData, mem, ops::Range};
+use std::{any, fmt, marker::PhantomData, ops::Range};
 
 use enumset::{EnumSet, EnumSetType};
 use sorted_iter::{assume::AssumeSortedByItemExt, sorted_iterator::SortedByItem, SortedIterator};
@@ -12,11 +12,12 @@ use crate::{div_ceil, Bitset};
 /// A bitset similar to [`BitMatrix`][super::BitMatrix],
 /// but with a fixed column and row count, indexed by `R` [`EnumSetType`].
 #[derive(Clone, PartialEq, Eq)]
-pub struct EnumBitMatrix<R: EnumSetType>(Bitset<Box<[u32]>>, PhantomData<R>);
+pub struct EnumBitMatrix<R: EnumSetType>(Bitset<Box<[u32]>>, u32, PhantomData<R>);
 impl<R: EnumSetType> fmt::Debug for EnumBitMatrix<R> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("EnumBitMatrix")
             .field(&any::type_name::<R>())
+            .field(&self.1)
             .field(&self.0)
             .finish()
     }
@@ -31,8 +32,8 @@ impl<R: EnumSetType> EnumBitMatrix<R> {
     #[must_use]
     pub fn new(width: u32) -> Self {
         let len = width.checked_mul(R::BIT_WIDTH).unwrap() as usize;
-        let data = vec![0; div_ceil(len, mem::size_of::<u32>())];
-        Self(Bitset(data.into_boxed_slice()), PhantomData)
+        let data = vec![0; div_ceil(len, u32::BITS as usize)];
+        Self(Bitset(data.into_boxed_slice()), width, PhantomData)
     }
     /// Enable bits from `iter` for given `row`.
     ///
@@ -49,10 +50,89 @@ impl<R: EnumSetType> EnumBitMatrix<R> {
             unsafe { self.0.enable_bit(to_set as usize).unwrap_unchecked() };
         }
     }
-    /// The width in bits of individual rows of this [`EnumBitMatrix`].
+    /// The width in bits of individual rows of this [`EnumBitMatrix`], as
+    /// passed to [`EnumBitMatrix::new`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::EnumBitMatrix;
+    /// use enumset::EnumSetType;
+    ///
+    /// #[derive(EnumSetType, Debug)]
+    /// enum Color { Red, Green, Blue }
+    ///
+    /// let mut matrix = EnumBitMatrix::<Color>::new(11);
+    /// assert_eq!(matrix.bit_width(), 11);
+    /// assert_eq!(matrix.set(Color::Blue, 10, true), Some(()));
+    /// assert_eq!(matrix.set(Color::Blue, 11, true), None);
+    /// ```
     #[must_use]
     pub const fn bit_width(&self) -> u32 {
-        self.0 .0.len() as u32 / R::BIT_WIDTH
+        self.1
+    }
+    /// Set bit at column `index` of `row` to `value`.
+    ///
+    /// Returns `None` and does nothing if `index` is not within
+    /// [`bit_width`](Self::bit_width).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::EnumBitMatrix;
+    /// use enumset::EnumSetType;
+    ///
+    /// #[derive(EnumSetType, Debug)]
+    /// enum Color { Red, Green, Blue }
+    ///
+    /// let mut matrix = EnumBitMatrix::<Color>::new(10);
+    /// matrix.set(Color::Green, 3, true);
+    /// matrix.set(Color::Green, 7, true);
+    /// assert_eq!(matrix.row(Color::Green, 0..10).collect::<Vec<_>>(), [3, 7]);
+    ///
+    /// matrix.set(Color::Green, 3, false);
+    /// matrix.toggle(Color::Green, 4);
+    /// assert_eq!(matrix.row(Color::Green, 0..10).collect::<Vec<_>>(), [4, 7]);
+    ///
+    /// matrix.set(Color::Blue, 1, true);
+    /// matrix.clear_row(Color::Green);
+    /// assert_eq!(matrix.row(Color::Green, 0..10).count(), 0);
+    /// assert_eq!(matrix.row(Color::Blue, 0..10).collect::<Vec<_>>(), [1]);
+    /// ```
+    #[inline]
+    pub fn set(&mut self, row: R, index: usize, value: bool) -> Option<()> {
+        let bit = self.bit_index(row, index)?;
+        if value {
+            self.0.enable_bit(bit)
+        } else {
+            self.0.disable_bit(bit)
+        }
+    }
+    /// Flip bit at column `index` of `row`.
+    ///
+    /// Returns `None` and does nothing if `index` is not within
+    /// [`bit_width`](Self::bit_width).
+    #[inline]
+    pub fn toggle(&mut self, row: R, index: usize) -> Option<()> {
+        let bit = self.bit_index(row, index)?;
+        if self.0.bit(bit) {
+            self.0.disable_bit(bit)
+        } else {
+            self.0.enable_bit(bit)
+        }
+    }
+    /// Disable all bits in `row`.
+    pub fn clear_row(&mut self, row: R) {
+        let width = self.bit_width() as usize;
+        let start = row.enum_into_u32() as usize * width;
+        self.0.disable_range(start..start + width);
+    }
+    /// Position in the underlying bitset of column `index` in `row`,
+    /// `None` if `index` is not within [`bit_width`](Self::bit_width).
+    fn bit_index(&self, row: R, index: usize) -> Option<usize> {
+        let width = self.bit_width() as usize;
+        let start = row.enum_into_u32() as usize * width;
+        (index < width).then_some(start + index)
     }
     /// Iterate over enabled bits in `row`, limited to provided `range`.
     ///
@@ -78,11 +158,65 @@ impl<R: EnumSetType> EnumBitMatrix<R> {
 
     /// Iterate over enabled bits in all `rows`, limited to provided `range`.
     ///
+    /// Columns enabled in several of `rows` are visited once.
     /// [`Rows`] is a sorted iterator.
     #[must_use]
-    pub const fn rows(&self, rows: EnumSet<R>, range: Range<u32>) -> Rows<R> {
+    pub const fn rows(&self, rows: EnumSet<R>, range: Range<u32>) -> Rows<'_, R> {
         Rows { range, rows, bitset: self }
     }
+    /// Iterate over columns enabled in any of `rows`.
+    ///
+    /// Same as [`EnumBitMatrix::rows`], with the full row range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::EnumBitMatrix;
+    /// use enumset::EnumSetType;
+    ///
+    /// #[derive(EnumSetType, Debug)]
+    /// enum Color { Red, Green, Blue }
+    ///
+    /// let mut matrix = EnumBitMatrix::<Color>::new(10);
+    /// matrix.set_row(Color::Red, [5, 8].into_iter());
+    /// matrix.set_row(Color::Green, [2, 5].into_iter());
+    /// matrix.set_row(Color::Blue, [1].into_iter());
+    ///
+    /// let union: Vec<_> = matrix.all_set(Color::Red | Color::Green).collect();
+    /// assert_eq!(union, [2, 5, 8]);
+    /// ```
+    #[must_use]
+    pub const fn all_set(&self, rows: EnumSet<R>) -> Rows<'_, R> {
+        self.rows(rows, 0..self.bit_width())
+    }
+    /// Iterate over all rows of this matrix, with the enabled columns of
+    /// each row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::EnumBitMatrix;
+    /// use enumset::EnumSetType;
+    ///
+    /// #[derive(EnumSetType, Debug)]
+    /// enum Color { Red, Green, Blue }
+    ///
+    /// let mut matrix = EnumBitMatrix::<Color>::new(10);
+    /// matrix.set_row(Color::Red, [5, 8].into_iter());
+    /// matrix.set_row(Color::Blue, [1].into_iter());
+    ///
+    /// let rows: Vec<_> = matrix.iter().map(|(k, row)| (k, row.collect::<Vec<_>>())).collect();
+    /// assert_eq!(
+    ///     rows,
+    ///     [(Color::Red, vec![5, 8]), (Color::Green, vec![]), (Color::Blue, vec![1])],
+    /// );
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (R, impl SortedIterator<Item = u32> + '_)> + '_ {
+        let width = self.bit_width();
+        EnumSet::<R>::all()
+            .iter()
+            .map(move |row| (row, self.row(row, 0..width)))
+    }
 }
 
 /// Iterator from [`EnumBitMatrix::rows`].
@@ -101,11 +235,14 @@ impl<'a, R: EnumSetType> Iterator for Rows<'a, R> {
             return None;
         }
         let range = self.range.clone();
-        self.range.start += 1;
-
-        self.rows
+        let next = self
+            .rows
             .iter()
-            .find_map(|row| self.bitset.row(row, range.clone()).next())
+            .filter_map(|row| self.bitset.row(row, range.clone()).next())
+            .min();
+
+        self.range.start = next.map_or(range.end, |next| next + 1);
+        next
     }
 }
 impl<R: EnumSetType> SortedByItem for Rows<'_, R> {}
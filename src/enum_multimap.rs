@@ -2,12 +2,13 @@
 //!
 //! [multimap]: https://en.wikipedia.org/wiki/Multimap
 
-use std::{fmt, marker::PhantomData, mem::size_of};
+use std::{fmt, iter, marker::PhantomData, mem::size_of};
 
 use enumset::{EnumSet, EnumSetType};
 
 use crate::JaggedArray;
 
+#[derive(Clone, PartialEq, Eq)]
 struct OwnAsRefSlice<const U: usize>(Box<[u32; U]>);
 impl<const U: usize> AsRef<[u32]> for OwnAsRefSlice<U> {
     fn as_ref(&self) -> &[u32] {
@@ -22,7 +23,31 @@ impl<const U: usize> AsRef<[u32]> for OwnAsRefSlice<U> {
 /// Use it as follow:
 /// `EnumMultimap<MyEnumSet, ModifyIndex, { (MyEnumSet::BIT_WIDTH - 1) as usize }>`
 ///
+/// To use integer keys instead, see [`jagged_array::KeyedBuilder`], which
+/// doesn't require the `enumset` feature.
+///
+/// # Example
+///
+/// ```
+/// use datazoo::enum_multimap::Builder;
+/// use enumset::EnumSetType;
+///
+/// #[derive(EnumSetType, Debug)]
+/// enum Color { Red, Green, Blue }
+///
+/// let mut builder = Builder::<Color, u32, 2>::new();
+/// builder.insert(Color::Red, [1, 2].into_iter());
+/// builder.insert(Color::Green, [].into_iter());
+/// builder.insert(Color::Blue, [3].into_iter());
+/// let multimap = builder.build();
+///
+/// assert_eq!(multimap.row(Color::Blue), &[3]);
+/// assert_eq!(multimap.clone(), multimap);
+/// ```
+///
+/// [`jagged_array::KeyedBuilder`]: crate::jagged_array::KeyedBuilder
 /// [multimap]: https://en.wikipedia.org/wiki/Multimap
+#[derive(Clone, PartialEq, Eq)]
 pub struct EnumMultimap<K: EnumSetType, V, const CLM: usize> {
     inner: JaggedArray<V, u32, OwnAsRefSlice<CLM>>,
     _key: PhantomData<K>,
@@ -53,12 +78,69 @@ impl<K: EnumSetType, V, const CLM: usize> EnumMultimap<K, V, CLM> {
     pub fn get(&self, direct_index: usize) -> Option<&V> {
         self.inner.get(direct_index)
     }
+    /// Create an [`EnumMultimap`] where the row of each key is
+    /// `rows[key.enum_into_u32()]`.
+    ///
+    /// Unlike [`Builder`], all rows must be known upfront. Fails to compile if
+    /// `N` is not the number of variants of `K` (ie: `CLM + 1`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::EnumMultimap;
+    /// use enumset::EnumSetType;
+    ///
+    /// #[derive(EnumSetType, Debug)]
+    /// enum Color { Red, Green, Blue }
+    ///
+    /// let multimap = EnumMultimap::<Color, u32, 2>::from_rows([vec![1, 2], vec![], vec![3]]);
+    ///
+    /// assert_eq!(multimap.row(Color::Red), &[1, 2]);
+    /// assert_eq!(multimap.row(Color::Green), &[]);
+    /// assert_eq!(multimap.row(Color::Blue), &[3]);
+    /// ```
+    ///
+    /// Passing the wrong number of rows doesn't compile:
+    ///
+    /// ```compile_fail
+    /// # use datazoo::EnumMultimap;
+    /// # use enumset::EnumSetType;
+    /// # #[derive(EnumSetType, Debug)]
+    /// # enum Color { Red, Green, Blue }
+    /// let multimap = EnumMultimap::<Color, u32, 2>::from_rows([vec![1, 2], vec![3]]);
+    /// ```
+    #[must_use]
+    pub fn from_rows<const N: usize>(rows: [Vec<V>; N]) -> Self {
+        const { assert!(N == CLM + 1, "`rows` must have one row per key") };
+        Self::from_row_iter(rows)
+    }
+    /// Create an [`EnumMultimap`] from exactly `CLM + 1` rows, in key order.
+    fn from_row_iter<R: IntoIterator<Item = V>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let () = Builder::<K, V, CLM>::_COMPILE_TIME_ERROR;
+
+        let mut ends = Box::new([0; CLM]);
+        let mut data = Vec::new();
+        for (i, values) in rows.into_iter().enumerate() {
+            data.extend(values);
+            if i < CLM {
+                ends[i] = data.len() as u32;
+            }
+        }
+        // SAFETY:
+        // - by construction, the ends are always increasing
+        // - by construction, ends never grow beyond the total size of `data`.
+        let inner = unsafe {
+            JaggedArray::new(OwnAsRefSlice(ends), data.into_boxed_slice()).unwrap_unchecked()
+        };
+        EnumMultimap { inner, _key: PhantomData }
+    }
 }
 
 /// Build a [`EnumMultimap`].
 #[derive(Debug, Clone)]
 pub struct Builder<K, V, const CLM: usize> {
-    rows: Vec<Box<[V]>>,
+    /// One slot per key, in key order, `None` for keys without row.
+    rows: Vec<Option<Box<[V]>>>,
     _key: PhantomData<K>,
 }
 impl<K: EnumSetType, V, const CLM: usize> Default for Builder<K, V, CLM> {
@@ -78,32 +160,41 @@ impl<K: EnumSetType, V, const CLM: usize> Builder<K, V, CLM> {
     /// Create a new [`EnumMultimap`] builder.
     #[must_use]
     pub fn new() -> Self {
-        Builder { rows: Vec::with_capacity(CLM), _key: PhantomData }
+        let rows = iter::repeat_with(|| None).take(CLM + 1).collect();
+        Builder { rows, _key: PhantomData }
     }
     /// Insert provided `values` into `key` row.
+    ///
+    /// Keys can be inserted in any order. Inserting twice the same `key`
+    /// replaces its previous row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::enum_multimap::Builder;
+    /// use enumset::EnumSetType;
+    ///
+    /// #[derive(EnumSetType, Debug)]
+    /// enum Color { Red, Green, Blue }
+    ///
+    /// let mut builder = Builder::<Color, u32, 2>::new();
+    /// builder.insert(Color::Blue, [3].into_iter());
+    /// builder.insert(Color::Red, [0].into_iter());
+    /// builder.insert(Color::Red, [1, 2].into_iter());
+    /// let multimap = builder.build();
+    ///
+    /// assert_eq!(multimap.row(Color::Red), &[1, 2]);
+    /// assert_eq!(multimap.row(Color::Green), &[]);
+    /// assert_eq!(multimap.row(Color::Blue), &[3]);
+    /// ```
     pub fn insert(&mut self, key: K, values: impl Iterator<Item = V>) {
         let row = key.enum_into_u32() as usize;
-        self.rows.insert(row, values.collect());
+        self.rows[row] = Some(values.collect());
     }
     /// Create the [`EnumMultimap`] from provided rows.
     #[must_use]
     pub fn build(self) -> EnumMultimap<K, V, CLM> {
-        let mut end = 0;
-        let mut ends = Box::new([0; CLM]);
-        let mut data = Vec::new();
-        for (i, values) in self.rows.into_iter().enumerate() {
-            end += values.len() as u32;
-            data.extend(values.into_vec());
-            if i < CLM {
-                ends[i] = end;
-            }
-        }
-        // SAFETY:
-        // - by construction, the ends are always increasing
-        // - by construction, ends never grow beyond the total size of `data`.
-        let inner = unsafe {
-            JaggedArray::new(OwnAsRefSlice(ends), data.into_boxed_slice()).unwrap_unchecked()
-        };
-        EnumMultimap { inner, _key: PhantomData }
+        let rows = self.rows.into_iter();
+        EnumMultimap::from_row_iter(rows.map(|values| values.unwrap_or_default().into_vec()))
     }
 }
@@ -3,7 +3,7 @@
 //! [multimap]: https://en.wikipedia.org/wiki/Multimap
 use std::marker::PhantomData;
 
-use crate::{BitMatrix, Index};
+use crate::{jagged_array, BitMatrix, Index, JaggedArray};
 
 /// A [multimap] that goes from an integer to multiple integers.
 ///
@@ -54,10 +54,115 @@ use crate::{BitMatrix, Index};
 pub struct IndexMultimap<K: Index, V: From<usize>> {
     assocs: BitMatrix,
     value_count: usize,
+    key_count: usize,
     _idx_ty: PhantomData<fn(K, V)>,
 }
 impl<K: Index, V: From<usize>> IndexMultimap<K, V> {
+    /// Create an empty [`IndexMultimap`] where you can [insert] keys in
+    /// `0..key_len` associated with values in `0..value_len`.
+    ///
+    /// The backing [`BitMatrix`] is allocated upfront, inserting within those
+    /// bounds never reallocates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::IndexMultimap;
+    ///
+    /// let mut multimap = IndexMultimap::<usize, usize>::with_bounds(4, 10);
+    /// assert_eq!(multimap.insert(&0, &9), Some(()));
+    /// assert_eq!(multimap.insert(&0, &3), Some(()));
+    /// assert_eq!(multimap.insert(&3, &3), Some(()));
+    ///
+    /// assert_eq!(multimap.insert(&0, &10), None);
+    ///
+    /// assert_eq!(multimap.get(&0).collect::<Vec<_>>(), [3, 9]);
+    /// assert_eq!(multimap.get(&3).collect::<Vec<_>>(), [3]);
+    /// assert_eq!(multimap.get(&2).count(), 0);
+    /// ```
+    ///
+    /// [insert]: Self::insert
+    #[must_use]
+    pub fn with_bounds(key_len: usize, value_len: usize) -> Self {
+        IndexMultimap {
+            assocs: BitMatrix::new_with_size(value_len, key_len),
+            value_count: value_len,
+            key_count: key_len,
+            _idx_ty: PhantomData,
+        }
+    }
+    /// Associate `value` to `key`.
+    ///
+    /// Returns `None` and does nothing if `key` or `value` is out of bound.
+    /// Out of bound keys and values are those equal or larger than the
+    /// `key_len` and `value_len` passed to [`Self::with_bounds`] (or the
+    /// largest key + 1 and largest value + 1 when built with `FromIterator`).
+    ///
+    /// This is checked against the declared bounds, not the size of the
+    /// backing [`BitMatrix`], which may have extra rows.
+    pub fn insert(&mut self, key: &K, value: &V) -> Option<()>
+    where
+        V: Index,
+    {
+        let (width, value) = (self.value_count, value.get());
+        if value >= width || key.get() >= self.key_count {
+            return None;
+        }
+        self.assocs.enable_bit(width, value, key.get())
+    }
+    /// The backing [`BitMatrix`], with one row per key.
+    ///
+    /// Rows are [`Self::value_len`] wide, use it as the `width` argument to
+    /// `BitMatrix` methods.
+    #[must_use]
+    pub const fn as_matrix(&self) -> &BitMatrix {
+        &self.assocs
+    }
+    /// The upper bound (exclusive) of values in this multimap.
+    #[must_use]
+    pub const fn value_len(&self) -> usize {
+        self.value_count
+    }
+    /// How many `(key, value)` associations are in this multimap.
+    ///
+    /// This counts the enabled bits of every row.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let height = self.assocs.height(self.value_count);
+        self.assocs.row_popcounts(self.value_count, height).sum()
+    }
+    /// Whether this multimap has no associations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Remove all associations, keeping the bounds of this multimap.
+    ///
+    /// This doesn't reallocate, so the multimap can be refilled within the
+    /// same bounds without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::IndexMultimap;
+    ///
+    /// let mut multimap = IndexMultimap::<usize, usize>::with_bounds(4, 10);
+    /// multimap.insert(&0, &9).unwrap();
+    /// multimap.insert(&3, &3).unwrap();
+    /// assert_eq!(multimap.len(), 2);
+    ///
+    /// multimap.clear();
+    /// assert!(multimap.is_empty());
+    /// assert_eq!(multimap.get(&0).count(), 0);
+    ///
+    /// assert_eq!(multimap.insert(&3, &9), Some(()));
+    /// assert_eq!(multimap.get(&3).collect::<Vec<_>>(), [9]);
+    /// ```
+    pub fn clear(&mut self) {
+        self.assocs.clear();
+    }
     /// Get the values associated with given `K`
+    #[doc(alias = "neighbors")]
     pub fn get<'a>(&'a self, key: &K) -> impl Iterator<Item = V> + 'a {
         let index = key.get();
         let max_index = self.assocs.height(self.value_count);
@@ -66,6 +171,100 @@ impl<K: Index, V: From<usize>> IndexMultimap<K, V> {
             .into_iter()
             .flatten()
     }
+    /// Convert this multimap into a [`JaggedArray`], where row `i` holds
+    /// the values associated with key `i`, in ascending order.
+    ///
+    /// `JaggedArray` is more compact than `IndexMultimap` when there are few
+    /// values per key. The returned `JaggedArray` has exactly one row per
+    /// key, keys without values have an empty row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::IndexMultimap;
+    ///
+    /// let assocs = [(0, 1), (0, 5), (2, 3), (3, 0), (3, 5)];
+    /// let multimap: IndexMultimap<usize, usize> = assocs.into_iter().collect();
+    ///
+    /// let jagged = multimap.to_jagged();
+    /// assert_eq!(jagged.row(0), &[1, 5]);
+    /// assert_eq!(jagged.row(1), &[]);
+    /// assert_eq!(jagged.row(3), &[0, 5]);
+    /// assert_eq!(jagged.height(), 4);
+    ///
+    /// let round_trip: IndexMultimap<usize, usize> = jagged
+    ///     .rows_iter()
+    ///     .enumerate()
+    ///     .flat_map(|(k, row)| row.iter().map(move |v| (k, *v)))
+    ///     .collect();
+    /// for key in 0..4 {
+    ///     assert!(round_trip.get(&key).eq(multimap.get(&key)));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn to_jagged(&self) -> JaggedArray<V> {
+        let width = self.value_count;
+
+        let mut builder = jagged_array::Builder::new();
+        for y in 0..self.key_count {
+            builder.add_row(self.assocs.row(width, y).map(V::from));
+        }
+        builder.build()
+    }
+    /// How many values are associated with `key`.
+    ///
+    /// This counts the enabled bits of the row of `key` a block at a time,
+    /// without visiting each value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::IndexMultimap;
+    ///
+    /// let multimap: IndexMultimap<usize, usize> = [(0, 1), (0, 5), (2, 3)].into_iter().collect();
+    /// assert_eq!(multimap.degree(&0), 2);
+    /// assert_eq!(multimap.degree(&1), 0);
+    /// assert_eq!(multimap.degree(&9), 0);
+    /// ```
+    #[must_use]
+    pub fn degree(&self, key: &K) -> usize {
+        let (width, index) = (self.value_count, key.get());
+        if index >= self.assocs.height(width) {
+            return 0;
+        }
+        self.assocs.row_popcount(width, index)
+    }
+}
+impl<K: Index + From<usize>> IndexMultimap<K, K> {
+    /// Associate `a` to `b` **and** `b` to `a`, using this multimap as a
+    /// symmetric relation (for example, the edges of an undirected graph).
+    ///
+    /// Returns `None` and does nothing if either association is out of bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::IndexMultimap;
+    ///
+    /// let mut graph = IndexMultimap::<usize, usize>::with_bounds(4, 4);
+    /// graph.insert_symmetric(&0, &2).unwrap();
+    /// graph.insert_symmetric(&2, &3).unwrap();
+    ///
+    /// assert_eq!(graph.get(&2).collect::<Vec<_>>(), [0, 3]);
+    /// assert_eq!(graph.get(&3).collect::<Vec<_>>(), [2]);
+    /// assert_eq!(graph.degree(&2), 2);
+    ///
+    /// assert_eq!(graph.insert_symmetric(&0, &4), None);
+    /// assert_eq!(graph.degree(&0), 1);
+    /// ```
+    pub fn insert_symmetric(&mut self, a: &K, b: &K) -> Option<()> {
+        let in_bound = |i: usize| i < self.value_count && i < self.key_count;
+        if !in_bound(a.get()) || !in_bound(b.get()) {
+            return None;
+        }
+        self.insert(a, b)?;
+        self.insert(b, a)
+    }
 }
 impl<K: Index, V: From<usize> + Index> FromIterator<(K, V)> for IndexMultimap<K, V> {
     /// Create a [`IndexMultimap`] with all associations.
@@ -90,6 +289,90 @@ impl<K: Index, V: From<usize> + Index> FromIterator<(K, V)> for IndexMultimap<K,
         for (key, value) in &*key_values {
             assocs.enable_bit(width, value.get(), key.get()).unwrap();
         }
-        IndexMultimap { assocs, value_count: width, _idx_ty: PhantomData }
+        IndexMultimap {
+            assocs,
+            value_count: width,
+            key_count: height,
+            _idx_ty: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+
+    #[test]
+    fn empty_from_iter() {
+        let multimap: IndexMultimap<usize, usize> = iter::empty().collect();
+
+        assert!(multimap.as_matrix().is_empty());
+        assert_eq!(multimap.value_len(), 0);
+        assert_eq!(multimap.get(&0).count(), 0);
+        assert_eq!(multimap.get(&10).count(), 0);
+        assert_eq!(multimap.degree(&0), 0);
+        assert_eq!(multimap.to_jagged().rows_iter().flatten().count(), 0);
+    }
+    #[test]
+    fn zero_dimension() {
+        let mut no_values = IndexMultimap::<usize, usize>::with_bounds(4, 0);
+        assert!(no_values.as_matrix().is_empty());
+        assert_eq!(no_values.insert(&0, &0), None);
+        assert_eq!(no_values.get(&0).count(), 0);
+        assert_eq!(no_values.to_jagged().height(), 4);
+
+        let mut no_keys = IndexMultimap::<usize, usize>::with_bounds(0, 4);
+        assert!(no_keys.as_matrix().is_empty());
+        assert_eq!(no_keys.insert(&0, &0), None);
+        assert_eq!(no_keys.insert_symmetric(&0, &0), None);
+        assert_eq!(no_keys.get(&0).count(), 0);
+
+        no_values.clear();
+        no_keys.clear();
+        assert!(no_values.is_empty() && no_keys.is_empty());
+    }
+    #[test]
+    fn insert_key_past_bound() {
+        // 3 keys × 5 values fit in a single block, which has room for 6 rows.
+        let mut multimap = IndexMultimap::<usize, usize>::with_bounds(3, 5);
+        assert!(multimap.as_matrix().height(5) > 3);
+        assert_eq!(multimap.to_jagged().height(), 3);
+
+        assert_eq!(multimap.insert(&2, &4), Some(()));
+        assert_eq!(multimap.insert(&3, &0), None);
+        assert_eq!(multimap.insert_symmetric(&3, &1), None);
+        assert_eq!(multimap.get(&3).count(), 0);
+        assert_eq!(multimap.len(), 1);
+
+        let mut collected: IndexMultimap<usize, usize> = [(1, 1), (0, 4)].into_iter().collect();
+        assert_eq!(collected.insert(&1, &0), Some(()));
+        assert_eq!(collected.insert(&2, &0), None);
+    }
+    #[test]
+    fn clear_refill() {
+        let frames: [&[(usize, usize)]; 3] = [
+            &[(0, 1), (0, 5), (2, 3), (3, 0), (3, 5)],
+            &[(1, 1), (3, 5), (3, 5)],
+            &[(2, 0), (2, 1), (2, 2), (2, 3), (2, 4), (2, 5)],
+        ];
+        let mut multimap = IndexMultimap::<usize, usize>::with_bounds(4, 6);
+        for assocs in frames {
+            multimap.clear();
+            assert_eq!(multimap.len(), 0);
+            assert!((0..4).all(|k| multimap.get(&k).count() == 0));
+
+            for (key, value) in assocs {
+                multimap.insert(key, value).unwrap();
+            }
+            let expected: IndexMultimap<usize, usize> = assocs.iter().copied().collect();
+            for key in 0..4 {
+                assert!(multimap.get(&key).eq(expected.get(&key)), "{assocs:?}");
+                assert_eq!(multimap.degree(&key), expected.get(&key).count());
+            }
+            assert_eq!(multimap.len(), expected.len());
+        }
+        assert_eq!(multimap.len(), 6);
     }
 }
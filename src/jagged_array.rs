@@ -1,7 +1,11 @@
 //! A variable length matrix optimized for read-only rows.
 
 use std::ops::Bound::{Excluded, Included, Unbounded};
-use std::{fmt, marker::PhantomData, ops::RangeBounds};
+use std::{
+    fmt, iter,
+    marker::PhantomData,
+    ops::{Range, RangeBounds},
+};
 
 use thiserror::Error;
 
@@ -15,7 +19,7 @@ pub enum Error {
     #[error(
         "Cannot build JaggedArray: `ends` represents the end of each row in `data`, \
         it should be monotonically increasing. \
-        Found `end` at position {i} lower than `end` at position {}", .i - 1
+        Found `end` at position {i} lower than the `end` preceding it"
     )]
     BadEnd { i: usize },
     /// An `end` in `ends` was too large.
@@ -26,6 +30,24 @@ pub enum Error {
     TooLongEnd { i: usize, len: usize, end: usize },
 }
 
+/// [`JaggedArray::try_get_rows`] error.
+///
+/// Row indices are those of the first included row (`start`) and the first
+/// excluded row (`end`).
+#[allow(missing_docs)]
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum RowRangeError {
+    /// The first row of the range is past the last row of the array.
+    #[error("Row range starts at {start}, past the array height ({height})")]
+    StartPastEnd { start: usize, height: usize },
+    /// The range extends past the last row of the array.
+    #[error("Row range ends at {end}, past the array height ({height})")]
+    EndOutOfBounds { end: usize, height: usize },
+    /// The start of the range is greater than its end.
+    #[error("Row range starts at {start}, after its end ({end})")]
+    Inverted { start: usize, end: usize },
+}
+
 /// A matrix of variable length row.
 ///
 /// # Limitation
@@ -59,6 +81,9 @@ pub enum Error {
 /// // This has 9 rows, and all but the last row have a maximum size of 2¹⁶
 /// let compact_array = JaggedArray::<&str, u16, [u16; 8]>::new([0; 8], my_strs.into());
 /// ```
+///
+/// See [`FixedJaggedArray`] and [`Builder::build_fixed`] to build such an array
+/// row by row.
 #[derive(PartialEq, Eq, Clone)]
 pub struct JaggedArray<V, I: Index = u32, E: AsRef<[I]> = Box<[I]>, VS: AsRef<[V]> = Box<[V]>> {
     ends: E,
@@ -66,6 +91,13 @@ pub struct JaggedArray<V, I: Index = u32, E: AsRef<[I]> = Box<[I]>, VS: AsRef<[V
     _i: PhantomData<fn([I], [V])>,
 }
 
+/// A [`JaggedArray`] with a fixed number of rows, storing its row ends
+/// in an array instead of a `Box`.
+///
+/// `ENDS` is the number of rows minus one. Use [`Builder::build_fixed`] to
+/// create one.
+pub type FixedJaggedArray<V, const ENDS: usize, I = u32> = JaggedArray<V, I, [I; ENDS]>;
+
 impl<V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> JaggedArray<V, I, E, VS> {
     /// How many cells are contained in this `JaggedArray`.
     #[inline]
@@ -185,7 +217,83 @@ impl<V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> JaggedArray<V, I, E, VS> {
     /// ```
     #[must_use]
     pub fn get_row(&self, index: usize) -> Option<&[V]> {
-        self.get_rows(index..=index)
+        self.data.as_ref().get(self.row_bounds(index)?)
+    }
+    /// Range of indices in the flat data array of the row at given `index`.
+    ///
+    /// Use this to index a collection sharing the layout of this `JaggedArray`.
+    /// Returns `None` if `index` is out of bound (`index >= self.height()`).
+    ///
+    /// # Example
+    /// ```rust
+    /// let array = datazoo::jagged_array::Builder::<i64>::new()
+    ///     .add_row([1, 2, 3]).add_row([4, 5, 6]).add_row([]).add_row([7, 8, 9])
+    ///     .build();
+    /// let meta = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+    ///
+    /// assert_eq!(array.row_bounds(1), Some(3..6));
+    /// assert_eq!(array.row_bounds(2), Some(6..6));
+    /// assert_eq!(array.row_bounds(3), Some(6..9));
+    /// assert_eq!(array.row_bounds(4), None);
+    /// assert_eq!(&meta[array.row_bounds(3).unwrap()], &["g", "h", "i"]);
+    /// ```
+    #[must_use]
+    pub fn row_bounds(&self, index: usize) -> Option<Range<usize>> {
+        let ends = self.ends.as_ref();
+        let start = match index.checked_sub(1) {
+            None => 0,
+            Some(prev) => ends.get(prev)?.get(),
+        };
+        let end = match ends.get(index) {
+            Some(end) => end.get(),
+            None if index == ends.len() => self.len(),
+            None => return None,
+        };
+        Some(start..end)
+    }
+    /// Binary search `x` in the row at `index`, assuming the row is sorted.
+    ///
+    /// Returns `None` if `index` is out of bound, otherwise the result of
+    /// [`slice::binary_search`] on the row, relative to the row start.
+    ///
+    /// # Example
+    /// ```rust
+    /// let array = datazoo::jagged_array::Builder::<i64>::new()
+    ///     .add_row([1, 2, 3]).add_row([4, 6, 8]).add_row([])
+    ///     .build();
+    ///
+    /// assert_eq!(array.binary_search_in_row(1, &6), Some(Ok(1)));
+    /// assert_eq!(array.binary_search_in_row(1, &5), Some(Err(1)));
+    /// assert_eq!(array.binary_search_in_row(2, &5), Some(Err(0)));
+    /// assert_eq!(array.binary_search_in_row(3, &5), None);
+    /// ```
+    #[must_use]
+    pub fn binary_search_in_row(&self, index: usize, x: &V) -> Option<Result<usize, usize>>
+    where
+        V: Ord,
+    {
+        self.get_row(index).map(|row| row.binary_search(x))
+    }
+    /// Whether the row at `index` contains `x`, assuming the row is sorted.
+    ///
+    /// Returns `false` if `index` is out of bound.
+    ///
+    /// # Example
+    /// ```rust
+    /// let array = datazoo::jagged_array::Builder::<i64>::new()
+    ///     .add_row([1, 2, 3]).add_row([4, 6, 8])
+    ///     .build();
+    ///
+    /// assert!(array.row_contains(1, &6));
+    /// assert!(!array.row_contains(0, &6));
+    /// assert!(!array.row_contains(2, &6));
+    /// ```
+    #[must_use]
+    pub fn row_contains(&self, index: usize, x: &V) -> bool
+    where
+        V: Ord,
+    {
+        matches!(self.binary_search_in_row(index, x), Some(Ok(_)))
     }
     /// Same as [`JaggedArray::row`], but for a range of rows instead of individual rows.
     ///
@@ -199,7 +307,8 @@ impl<V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> JaggedArray<V, I, E, VS> {
     }
     /// Same as [`JaggedArray::get_row`], but for a range of rows instead of individual rows.
     ///
-    /// Returns `None` if the range is out of bound.
+    /// Returns `None` if the range is out of bound or if its start is greater
+    /// than its end.
     ///
     /// # Example
     /// ```rust
@@ -210,38 +319,226 @@ impl<V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> JaggedArray<V, I, E, VS> {
     /// assert_eq!(array.get_rows(..), Some(&[1, 2, 3, 4, 5, 6, 7, 8, 9][..]));
     /// assert_eq!(array.get_rows(2..), Some(&[7, 8, 9][..]));
     /// assert_eq!(array.get_rows(2..3), Some(&[][..]));
+    /// assert_eq!(array.get_rows(3..2), None);
     /// ```
     #[inline]
     #[must_use]
     pub fn get_rows(&self, range: impl RangeBounds<usize>) -> Option<&[V]> {
+        self.try_get_rows(range).ok()
+    }
+    /// Same as [`JaggedArray::get_rows`], but returns why `range` is invalid
+    /// instead of `None`.
+    ///
+    /// # Errors
+    /// See [`RowRangeError`], errors are checked in the order of its variants.
+    ///
+    /// # Example
+    /// ```rust
+    /// use datazoo::jagged_array::RowRangeError;
+    ///
+    /// let array = datazoo::jagged_array::Builder::<i64>::new()
+    ///     .add_row([1, 2, 3]).add_row([4, 5, 6]).add_row([]).add_row([7, 8, 9])
+    ///     .build();
+    ///
+    /// assert_eq!(array.try_get_rows(1..3), Ok(&[4, 5, 6][..]));
+    /// assert_eq!(array.try_get_rows(5..), Err(RowRangeError::StartPastEnd { start: 5, height: 4 }));
+    /// assert_eq!(array.try_get_rows(1..=4), Err(RowRangeError::EndOutOfBounds { end: 5, height: 4 }));
+    /// assert_eq!(array.try_get_rows(3..2), Err(RowRangeError::Inverted { start: 3, end: 2 }));
+    /// ```
+    pub fn try_get_rows(&self, range: impl RangeBounds<usize>) -> Result<&[V], RowRangeError> {
         let ends = self.ends.as_ref();
-        let get_end = |i| match i {
-            n if n == ends.len() => Some(self.len()),
-            n if n >= ends.len() => None,
-            n => ends.get(n).map(I::get),
+        let height = self.height();
+        // Index in `data` of the first cell of row `i`, `i <= height`
+        let row_start = |i: usize| match i.checked_sub(1) {
+            None => 0,
+            Some(n) if n == ends.len() => self.len(),
+            Some(n) => ends[n].get(),
         };
+        // Bounds of `usize::MAX` saturate, they are out of bound anyway.
         let start = match range.start_bound() {
-            Included(0) | Unbounded => 0,
-            Included(&start) => get_end(start - 1)?,
-            Excluded(&start) => get_end(start)?,
+            Included(&start) => start,
+            Excluded(&start) => start.saturating_add(1),
+            Unbounded => 0,
         };
         let end = match range.end_bound() {
-            Excluded(0) => 0,
-            Excluded(&end) => get_end(end - 1)?,
-            Included(&end) => get_end(end)?,
-            Unbounded => self.len(),
+            Included(&end) => end.saturating_add(1),
+            Excluded(&end) => end,
+            Unbounded => height,
         };
+        if start > height {
+            return Err(RowRangeError::StartPastEnd { start, height });
+        }
+        if end > height {
+            return Err(RowRangeError::EndOutOfBounds { end, height });
+        }
         if start > end {
-            return None;
+            return Err(RowRangeError::Inverted { start, end });
         }
-        self.data.as_ref().get(start..end)
+        Ok(&self.data.as_ref()[row_start(start)..row_start(end)])
     }
     /// Iterate over every individual row slices of this `JaggedArray`.
-    pub const fn rows_iter(&self) -> JaggedArrayRows<V, I, E, VS> {
-        JaggedArrayRows { array: self, row: 0 }
+    pub const fn rows_iter(&self) -> JaggedArrayRows<'_, V, I, E, VS> {
+        JaggedArrayRows { array: self, row: 0, back: None }
+    }
+    /// How many rows of this `JaggedArray` are empty.
+    ///
+    /// This only reads `ends`, without accessing the rows.
+    ///
+    /// # Example
+    /// ```rust
+    /// let array = datazoo::jagged_array::Builder::<i64>::new()
+    ///     .add_row([]).add_row([1, 2, 3]).add_row([]).add_row([]).add_row([4])
+    ///     .build();
+    ///
+    /// assert_eq!(array.empty_row_count(), 3);
+    /// ```
+    #[must_use]
+    pub fn empty_row_count(&self) -> usize {
+        let ends = self.ends.as_ref().iter().map(I::get);
+        let starts = iter::once(0).chain(ends.clone());
+        let ends = ends.chain(iter::once(self.len()));
+        starts.zip(ends).filter(|(start, end)| start == end).count()
+    }
+    /// Iterate over non-empty rows of this `JaggedArray`, with their index.
+    ///
+    /// # Example
+    /// ```rust
+    /// let array = datazoo::jagged_array::Builder::<i64>::new()
+    ///     .add_row([]).add_row([1, 2, 3]).add_row([]).add_row([4])
+    ///     .build();
+    ///
+    /// let rows: Vec<_> = array.nonempty_rows().collect();
+    /// assert_eq!(rows, [(1, &[1, 2, 3][..]), (3, &[4][..])]);
+    /// ```
+    pub fn nonempty_rows(&self) -> impl Iterator<Item = (usize, &[V])> {
+        self.rows_iter()
+            .enumerate()
+            .filter(|(_, row)| !row.is_empty())
+    }
+    /// Apply `f` to each row, collecting the results in a `Vec` of exactly
+    /// [`Self::height`] elements.
+    ///
+    /// See [`Self::map_rows_boxed`] to collect into a `Box<[U]>` instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// let array = datazoo::jagged_array::Builder::<i64>::new()
+    ///     .add_row([1, 2, 3]).add_row([]).add_row([4, 5])
+    ///     .build();
+    ///
+    /// let sums = array.map_rows(|row| row.iter().sum::<i64>());
+    /// assert_eq!(sums, [6, 0, 9]);
+    /// ```
+    pub fn map_rows<U>(&self, f: impl FnMut(&[V]) -> U) -> Vec<U> {
+        self.rows_iter().map(f).collect()
+    }
+    /// Same as [`Self::map_rows`], but returns a `Box<[U]>`.
+    pub fn map_rows_boxed<U>(&self, f: impl FnMut(&[V]) -> U) -> Box<[U]> {
+        self.rows_iter().map(f).collect()
+    }
+    /// Iterate over each row folded into a single value, starting from a
+    /// clone of `init`.
+    ///
+    /// Rows are folded lazily, when the iterator is advanced.
+    ///
+    /// # Example
+    /// ```rust
+    /// let array = datazoo::jagged_array::Builder::<i64>::new()
+    ///     .add_row([1, 2, 3]).add_row([]).add_row([4, 5])
+    ///     .build();
+    ///
+    /// let sums: Vec<_> = array.row_reduce(0, |acc, v| acc + v).collect();
+    /// assert_eq!(sums, [6, 0, 9]);
+    ///
+    /// let maxes: Vec<_> = array.row_reduce(None, |acc, v| acc.max(Some(*v))).collect();
+    /// assert_eq!(maxes, [Some(3), None, Some(5)]);
+    /// ```
+    pub fn row_reduce<'a, U: Clone + 'a>(
+        &'a self,
+        init: U,
+        mut f: impl FnMut(U, &V) -> U + 'a,
+    ) -> impl ExactSizeIterator<Item = U> + DoubleEndedIterator + 'a {
+        self.rows_iter()
+            .map(move |row| row.iter().fold(init.clone(), &mut f))
     }
 }
 
+impl<'a, V, I: Index> JaggedArray<V, I, &'a [I], &'a [V]> {
+    /// Create a [`JaggedArray`] borrowing its `ends` and `data`, without copying them.
+    ///
+    /// This is [`JaggedArray::new`] with the borrowed storage types spelled out,
+    /// `ends` and `data` are validated the same way.
+    ///
+    /// # Errors
+    /// - An `ends[i] > data.len()`
+    /// - An `ends[i+1] < ends[i]`
+    ///
+    /// # Example
+    /// ```rust
+    /// use datazoo::JaggedArray;
+    ///
+    /// let ends: &[u32] = &[0, 3, 4];
+    /// let data = [0, 1, 2, 3, 4, 5];
+    /// let jagged = JaggedArray::borrowed(ends, &data).unwrap();
+    ///
+    /// assert_eq!(jagged.row(1), &[0, 1, 2]);
+    /// assert_eq!(jagged.row(3), &[4, 5]);
+    ///
+    /// assert!(JaggedArray::borrowed(&[3_u32, 1], &data).is_err());
+    /// ```
+    pub fn borrowed(ends: &'a [I], data: &'a [V]) -> Result<Self, Error> {
+        Self::new(ends, data)
+    }
+}
+impl<V, I: Index> JaggedArray<V, I> {
+    /// Concatenate `parts`, keeping their rows separate.
+    ///
+    /// The height of the returned `JaggedArray` is the sum of the height of
+    /// each part. If `parts` is empty, this returns a `JaggedArray` with a
+    /// single empty row.
+    ///
+    /// # Panics
+    /// If the end of a row in the concatenated array doesn't fit in `I`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use datazoo::{jagged_array::Builder, JaggedArray};
+    ///
+    /// let first = Builder::<i64>::new().add_row([1, 2]).add_row([]).build();
+    /// let second = Builder::<i64>::new().add_row([3]).add_row([4, 5]).build();
+    ///
+    /// let array = JaggedArray::concat([first, second]);
+    /// assert_eq!(array.height(), 4);
+    /// assert_eq!(array.into_vecs(), vec![vec![1, 2], vec![], vec![3], vec![4, 5]]);
+    /// ```
+    pub fn concat(parts: impl IntoIterator<Item = JaggedArray<V, I>>) -> Self {
+        let to_index = |end: usize| {
+            let index = I::new(end);
+            assert_eq!(
+                index.get(),
+                end,
+                "Cannot concat JaggedArray: the end of a row ({end}) doesn't fit in the index type `{}`",
+                std::any::type_name::<I>(),
+            );
+            index
+        };
+        let mut ends = Vec::new();
+        let mut data = Vec::new();
+        for (i, part) in parts.into_iter().enumerate() {
+            let offset = data.len();
+            if i != 0 {
+                ends.push(to_index(offset));
+            }
+            ends.extend(part.ends.iter().map(|end| to_index(end.get() + offset)));
+            data.extend(part.data.into_vec());
+        }
+        JaggedArray {
+            ends: ends.into(),
+            data: data.into(),
+            _i: PhantomData,
+        }
+    }
+}
 impl<V, I: Index, E: AsRef<[I]>> JaggedArray<V, I, E> {
     /// Turn this compact jagged array into a sparse representation.
     ///
@@ -256,18 +553,16 @@ impl<V, I: Index, E: AsRef<[I]>> JaggedArray<V, I, E> {
         let ends = ends.as_ref();
         let mut data = data.into_vec();
 
-        let mut iliffe = Vec::with_capacity(ends.len());
-        let mut last_end = 0;
+        let mut iliffe = Vec::with_capacity(ends.len() + 1);
 
-        // TODO(perf): this is slow as heck because each drain needs to move
-        // forward the end of the `data` vec, if we reverse ends here, we can
-        // skip the nonsense.
-        for end in ends {
-            let size = end.get() - last_end;
-            iliffe.push(data.drain(..size).collect());
-            last_end = end.get();
+        // Split rows from the back, so that we never move the tail of `data`.
+        for end in ends.iter().rev() {
+            iliffe.push(data.split_off(end.get()));
         }
+        // `data` is now the first row, drop the capacity of the split off rows.
+        data.shrink_to_fit();
         iliffe.push(data);
+        iliffe.reverse();
         iliffe
     }
 }
@@ -294,22 +589,58 @@ pub struct JaggedArrayRows<
     VS: AsRef<[V]> = Box<[V]>,
 > {
     array: &'j JaggedArray<V, I, E, VS>,
+    /// Next row to yield from the front.
     row: usize,
+    /// One past the next row to yield from the back, `None` until the
+    /// first call to `next_back`, meaning the array's height.
+    ///
+    /// This is lazy so that [`JaggedArray::rows_iter`] can be `const`.
+    back: Option<usize>,
 }
 
 impl<'j, V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> Clone for JaggedArrayRows<'j, V, I, E, VS> {
     fn clone(&self) -> Self {
-        Self { array: self.array, row: self.row }
+        Self { array: self.array, row: self.row, back: self.back }
+    }
+}
+impl<V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> JaggedArrayRows<'_, V, I, E, VS> {
+    #[inline]
+    fn back(&self) -> usize {
+        self.back.unwrap_or_else(|| self.array.height())
     }
 }
 
-impl<'j, V, I: Index, E: AsRef<[I]>> Iterator for JaggedArrayRows<'j, V, I, E> {
+impl<'j, V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> Iterator for JaggedArrayRows<'j, V, I, E, VS> {
     type Item = &'j [V];
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.row >= self.back() {
+            return None;
+        }
         self.row += 1;
         self.array.get_row(self.row - 1)
     }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back().saturating_sub(self.row);
+        (len, Some(len))
+    }
+}
+impl<V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> ExactSizeIterator
+    for JaggedArrayRows<'_, V, I, E, VS>
+{
+}
+impl<V, I: Index, E: AsRef<[I]>, VS: AsRef<[V]>> DoubleEndedIterator
+    for JaggedArrayRows<'_, V, I, E, VS>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let back = self.back();
+        if self.row >= back {
+            return None;
+        }
+        self.back = Some(back - 1);
+        self.array.get_row(back - 1)
+    }
 }
 
 //
@@ -361,13 +692,74 @@ impl<V, I: Index> Builder<V, I> {
     }
     /// Add all elements in `row` to the current row and mark it as a distinct
     /// row in the resulting [`JaggedArray`].
+    ///
+    /// # Panics
+    ///
+    /// If the total number of elements doesn't fit in `I`. For example,
+    /// with `I = u8`, when adding a row ending past the 255th element.
+    ///
+    /// ```should_panic
+    /// let mut builder = datazoo::jagged_array::Builder::<i64, u8>::new();
+    /// builder.add_row(0..200).add_row(0..100);
+    /// ```
     pub fn add_row(&mut self, row: impl IntoIterator<Item = V>) -> &mut Self {
         self.data.extend(row);
-        if let Some(last_end) = self.last_end.replace(I::new(self.data.len())) {
+        let len = self.data.len();
+        let end = I::new(len);
+        assert_eq!(
+            end.get(),
+            len,
+            "Cannot build JaggedArray: the end of a row ({len}) doesn't fit in the index type `{}`",
+            std::any::type_name::<I>(),
+        );
+        if let Some(last_end) = self.last_end.replace(end) {
             self.ends.push(last_end);
         }
         self
     }
+    /// Complete this [`JaggedArray`] as a [`FixedJaggedArray`] of exactly
+    /// `ENDS + 1` rows, consuming this `Builder`.
+    ///
+    /// # Panics
+    ///
+    /// If [`Self::add_row`] wasn't called exactly `ENDS + 1` times.
+    ///
+    /// ```should_panic
+    /// use datazoo::jagged_array::{Builder, FixedJaggedArray};
+    ///
+    /// let array: FixedJaggedArray<i64, 2> = Builder::new().add_row([1, 2]).build_fixed();
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::jagged_array::{Builder, FixedJaggedArray};
+    ///
+    /// let array: FixedJaggedArray<&str, 2, u16> = Builder::new()
+    ///     .add_row(["one"])
+    ///     .add_row([])
+    ///     .add_row(["two", "three"])
+    ///     .build_fixed();
+    ///
+    /// assert_eq!(array.height(), 3);
+    /// assert_eq!(array.row(2), &["two", "three"]);
+    /// ```
+    #[must_use]
+    pub fn build_fixed<const ENDS: usize>(&mut self) -> FixedJaggedArray<V, ENDS, I> {
+        let rows = self.ends.len() + usize::from(self.last_end.is_some());
+        assert_eq!(
+            rows,
+            ENDS + 1,
+            "Cannot build FixedJaggedArray: expected {} rows, got {rows}",
+            ENDS + 1,
+        );
+        let ends = std::mem::take(&mut self.ends);
+        let Ok(ends) = <[I; ENDS]>::try_from(ends) else {
+            unreachable!("`ends` has `rows - 1` elements");
+        };
+        let data = std::mem::take(&mut self.data);
+        JaggedArray { ends, data: data.into(), _i: PhantomData }
+    }
     /// Complete this [`JaggedArray`], consuming this `Builder`.
     #[must_use]
     pub fn build(&mut self) -> JaggedArray<V, I> {
@@ -380,6 +772,68 @@ impl<V, I: Index> Builder<V, I> {
         }
     }
 }
+
+/// Constructor for a [`JaggedArray`] used as a [multimap] with integer keys.
+///
+/// Unlike [`Builder`], rows can be added in any order. The number of keys
+/// is known upfront, this is the equivalent of
+/// [`EnumMultimap`](crate::EnumMultimap) when you don't want to depend on
+/// `enumset`.
+///
+/// # Example
+///
+/// ```
+/// use datazoo::jagged_array::KeyedBuilder;
+///
+/// let mut builder = KeyedBuilder::<usize, &str>::new(4);
+/// builder.insert(&2, ["two", "deux"]);
+/// builder.insert(&0, ["zero"]);
+/// builder.insert(&2, ["zwei"]);
+/// assert_eq!(builder.insert(&4, ["four"]), None);
+///
+/// let multimap = builder.build();
+/// assert_eq!(multimap.height(), 4);
+/// assert_eq!(multimap.row(0), &["zero"]);
+/// assert_eq!(multimap.row(1), &[] as &[&str]);
+/// assert_eq!(multimap.row(2), &["two", "deux", "zwei"]);
+/// assert_eq!(multimap.row(3), &[] as &[&str]);
+/// ```
+///
+/// [multimap]: https://en.wikipedia.org/wiki/Multimap
+pub struct KeyedBuilder<K, V, I = u32> {
+    rows: Vec<Vec<V>>,
+    _tys: PhantomData<fn(K, I)>,
+}
+impl<K: Index, V, I: Index> KeyedBuilder<K, V, I> {
+    /// Create a builder for a [`JaggedArray`] of `key_count` rows.
+    ///
+    /// Note that a `JaggedArray` always has at least one row, so the built
+    /// array has a single empty row if `key_count` is `0`.
+    #[must_use]
+    pub fn new(key_count: usize) -> Self {
+        let rows = iter::repeat_with(Vec::new).take(key_count).collect();
+        KeyedBuilder { rows, _tys: PhantomData }
+    }
+    /// Add `values` to the row of `key`.
+    ///
+    /// Returns `None` and does nothing if `key` is not lower than the
+    /// `key_count` passed to [`Self::new`].
+    pub fn insert(&mut self, key: &K, values: impl IntoIterator<Item = V>) -> Option<()> {
+        self.rows.get_mut(key.get())?.extend(values);
+        Some(())
+    }
+    /// Complete this [`JaggedArray`], where row `i` contains all values
+    /// inserted at key `i`.
+    #[must_use]
+    pub fn build(self) -> JaggedArray<V, I> {
+        let data_len = self.rows.iter().map(Vec::len).sum();
+        let mut builder = Builder::new_with_capacity(self.rows.len(), data_len);
+        for row in self.rows {
+            builder.add_row(row);
+        }
+        builder.build()
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -441,4 +895,111 @@ mod tests {
         assert_eq!(array.get_rows(2..5), Some(&[4, 5, 6, 7, 8, 9][..]));
         assert_eq!(array.get_rows(..), Some(&[1, 2, 3, 4, 5, 6, 7, 8, 9][..]));
     }
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_get_rows_out_of_range() {
+        let array = Builder::<i64>::new()
+            .add_row([])
+            .add_row([1, 2, 3])
+            .add_row([])
+            .add_row([4, 5])
+            .build();
+        let height = array.height();
+        assert_eq!(height, 4);
+
+        assert_eq!(array.get_rows(3..1), None);
+        assert_eq!(array.get_rows(2..0), None);
+        assert_eq!(array.get_rows(1..0), None);
+        assert_eq!(array.get_rows(5..), None);
+        assert_eq!(array.get_rows(..=height), None);
+        assert_eq!(array.get_rows(..height + 1), None);
+        assert_eq!(array.get_rows(usize::MAX..), None);
+        assert_eq!(array.get_rows(..=usize::MAX), None);
+        assert_eq!(array.get_rows((Excluded(usize::MAX), Unbounded)), None);
+
+        assert_eq!(array.get_rows(4..), Some(&[][..]));
+        assert_eq!(array.get_rows(..=height - 1), Some(&[1, 2, 3, 4, 5][..]));
+        assert_eq!(array.get_rows(..height), Some(&[1, 2, 3, 4, 5][..]));
+        assert_eq!(
+            array.get_rows((Excluded(0), Included(1))),
+            Some(&[1, 2, 3][..])
+        );
+    }
+    #[test]
+    fn test_into_vecs_many_rows() {
+        let expected: Vec<Vec<u32>> = (0..1000).map(|i| (0..i % 7).collect()).collect();
+        let mut builder = Builder::<u32>::new();
+        for row in &expected {
+            builder.add_row(row.iter().copied());
+        }
+        let vecs = builder.build().into_vecs();
+        assert_eq!(vecs, expected);
+        assert!(vecs.iter().all(|row| row.capacity() == row.len()));
+    }
+    #[test]
+    fn test_new_bad_first_end() {
+        let err = JaggedArray::new([5_u32], vec![0, 1, 2].into_boxed_slice()).unwrap_err();
+        assert!(matches!(err, Error::TooLongEnd { i: 0, len: 3, end: 5 }));
+        assert!(err.to_string().contains("position 0 (5)"));
+
+        // `BadEnd` can't be returned for the first end, but its message
+        // shouldn't underflow either.
+        let message = Error::BadEnd { i: 0 }.to_string();
+        assert!(message.contains("position 0 lower than the `end` preceding it"));
+
+        let err = JaggedArray::new([2_u32, 1], vec![0, 1, 2].into_boxed_slice()).unwrap_err();
+        assert!(matches!(err, Error::BadEnd { i: 1 }));
+    }
+    #[test]
+    fn test_iter_rows_double_ended() {
+        let array = Builder::<i64>::new()
+            .add_row([1])
+            .add_row([2, 3])
+            .add_row([])
+            .add_row([4])
+            .build();
+
+        let rev: Vec<_> = array.rows_iter().rev().collect();
+        assert_eq!(rev, [&[4][..], &[], &[2, 3], &[1]]);
+
+        let mut iter = array.rows_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(&[1][..]));
+        assert_eq!(iter.next_back(), Some(&[4][..]));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(&[][..]));
+        assert_eq!(iter.next(), Some(&[2, 3][..]));
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+    #[test]
+    fn test_small_index_fits() {
+        let array = Builder::<i64, u8>::new()
+            .add_row(0..200)
+            .add_row(200..255)
+            .add_row([])
+            .build();
+        assert_eq!(array.row(1), (200..255).collect::<Vec<_>>());
+        assert_eq!(array.row(2), &[]);
+    }
+    #[test]
+    #[should_panic(expected = "doesn't fit in the index type `u8`")]
+    fn test_small_index_overflow() {
+        let _ = Builder::<i64, u8>::new()
+            .add_row(0..200)
+            .add_row(0..100)
+            .build();
+    }
+    #[test]
+    fn test_concat() {
+        let empty = JaggedArray::<i64>::concat([]);
+        assert_eq!(empty.into_vecs(), vec![Vec::<i64>::new()]);
+
+        let single_row = || Builder::<i64>::new().add_row([1]).build();
+        let empty_row = || Builder::<i64>::new().add_row([]).build();
+        let parts = [empty_row(), single_row(), empty_row(), single_row()];
+        let array = JaggedArray::concat(parts);
+        assert_eq!(array.into_vecs(), vec![vec![], vec![1], vec![], vec![1]]);
+    }
 }
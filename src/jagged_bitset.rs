@@ -1,7 +1,7 @@
 //! A bit matrix similar to [`BitMatrix`](super::BitMatrix),
 //! but with columns of variable length like [`JaggedVec`](super::JaggedVec).
 
-use std::{fmt, iter, mem};
+use std::{fmt, ops::Range};
 
 use sorted_iter::{assume::AssumeSortedByItemExt, sorted_iterator::SortedByItem};
 
@@ -40,23 +40,52 @@ use crate::{div_ceil, Bitset, PackedIntArray};
 pub struct JaggedBitset {
     ends: PackedIntArray<usize, u32>,
     bits: Bitset<Box<[u32]>>,
+    /// Whether rows start at a `u32` block boundary, see [`Builder::aligned`].
+    aligned: bool,
 }
 impl JaggedBitset {
+    /// Range of bits in `self.bits` of row at `index`.
+    fn row_range(&self, index: usize) -> Option<Range<usize>> {
+        let start = index
+            .checked_sub(1)
+            .map_or(Some(0), |i| self.ends.get(&i))?;
+        let start = if self.aligned { align_block(start) } else { start };
+        let end = self.ends.get(&index)?;
+
+        Some(start as usize..end as usize)
+    }
     /// True if bits at column `x` and row `y` is enabled. False if not, or
     /// if `(x, y)` is not within the array.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::jagged_bitset;
+    ///
+    /// let jagged = jagged_bitset::Builder::with_capacity(3)
+    ///     .with_row([0, 2])
+    ///     .with_row([])
+    ///     .with_row([1, 40])
+    ///     .build();
+    ///
+    /// assert!(jagged.bit(2, 0));
+    /// assert!(!jagged.bit(1, 0));
+    /// assert!(jagged.bit(40, 2));
+    ///
+    /// // Out of bound columns and rows are disabled
+    /// assert!(!jagged.bit(0, 1));
+    /// assert!(!jagged.bit(3, 0));
+    /// assert!(!jagged.bit(0, 3));
+    /// ```
     #[inline]
     #[must_use]
     pub fn bit(&self, x: usize, y: usize) -> bool {
         if y >= self.capacity() {
             return false;
         }
-        let start = y.checked_sub(1).map_or(Some(0), |i| self.ends.get(&i));
-        let end = self.ends.get(&y);
-        let (Some(start), Some(end)) = (start, end) else {
+        let Some(Range { start, end }) = self.row_range(y) else {
             return false;
         };
-        let (start, end) = (start as usize, end as usize);
-
         if x >= (end - start) {
             return false;
         }
@@ -72,10 +101,47 @@ impl JaggedBitset {
         max.unwrap_or(0)
     }
     /// How many rows there are in this bitset matrix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::jagged_bitset;
+    ///
+    /// let jagged = jagged_bitset::Builder::with_capacity(3)
+    ///     .with_row([0, 2])
+    ///     .with_row([])
+    ///     .with_row([1, 40])
+    ///     .build();
+    ///
+    /// assert_eq!(jagged.height(), 3);
+    /// assert_eq!(jagged_bitset::Builder::new().build().height(), 0);
+    /// ```
     #[must_use]
     pub fn height(&self) -> usize {
-        let first_occupied = self.ends.rev_iter().next();
-        first_occupied.map_or(0, |(k, _)| k)
+        let last_occupied = self.ends.rev_iter().next();
+        last_occupied.map_or(0, |(k, _)| k + 1)
+    }
+    /// How many bits are enabled in all rows of this bitset matrix.
+    ///
+    /// Since rows never overlap, this counts the enabled bits of the whole
+    /// backing bitset, 32 bits at a time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::jagged_bitset;
+    ///
+    /// let jagged = jagged_bitset::Builder::with_capacity(3)
+    ///     .with_row([0, 2])
+    ///     .with_row([])
+    ///     .with_row([1, 40])
+    ///     .build();
+    ///
+    /// assert_eq!(jagged.total_count_ones(), 4);
+    /// ```
+    #[must_use]
+    pub fn total_count_ones(&self) -> usize {
+        self.bits.ones_count()
     }
     /// Return an upper bound of how many rows this jagged bitset has.
     #[inline]
@@ -97,12 +163,8 @@ impl JaggedBitset {
     #[inline]
     #[must_use]
     pub fn get_width(&self, index: usize) -> Option<u32> {
-        let start = index
-            .checked_sub(1)
-            .map_or(Some(0), |i| self.ends.get(&i))?;
-        let end = self.ends.get(&index)?;
-
-        Some(end - start)
+        let range = self.row_range(index)?;
+        Some(range.len() as u32)
     }
     /// Iterate over all enabled bits in given `index` row.
     ///
@@ -116,18 +178,80 @@ impl JaggedBitset {
     /// Returns `None` if the row is out of bound.
     #[must_use]
     pub fn get_row(&self, index: usize) -> Option<impl Iterator<Item = u32> + SortedByItem + '_> {
-        let start = index
-            .checked_sub(1)
-            .map_or(Some(0), |i| self.ends.get(&i))?;
-        let end = self.ends.get(&index)?;
+        let range = self.row_range(index)?;
+        let start = range.start as u32;
 
-        let range = start as usize..end as usize;
+        let is_not_empty = !range.is_empty();
         let bits = self.bits.ones_in_range(range).map(move |i| i - start);
         let bits = bits.assume_sorted_by_item();
 
-        let is_not_empty = start != end;
         Some(is_not_empty.then_some(bits).into_iter().flatten())
     }
+    /// The row at `index` as a borrowed [`Bitset`].
+    ///
+    /// Returns `None` if `index` is out of bound, or if this `JaggedBitset`
+    /// wasn't built with [`Builder::aligned`]. Bits of the returned bitset
+    /// at or past [`JaggedBitset::width`] are always disabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::jagged_bitset;
+    ///
+    /// let jagged = jagged_bitset::Builder::aligned(3)
+    ///     .with_row([0, 2])
+    ///     .with_row([1, 40])
+    ///     .build();
+    ///
+    /// let row = jagged.get_row_bitset(1).unwrap();
+    /// assert_eq!(row.ones().collect::<Vec<_>>(), [1, 40]);
+    /// assert_eq!(row.bit_len(), 64);
+    /// assert!(jagged.get_row_bitset(2).is_none());
+    ///
+    /// let packed = jagged_bitset::Builder::new().with_row([0, 2]).build();
+    /// assert!(packed.get_row_bitset(0).is_none());
+    /// ```
+    #[must_use]
+    pub fn get_row_bitset(&self, index: usize) -> Option<Bitset<&[u32]>> {
+        if !self.aligned {
+            return None;
+        }
+        let range = self.row_range(index)?;
+        let blocks = range.start / BLOCK_BITS..div_ceil(range.end, BLOCK_BITS);
+        Some(Bitset(self.bits.0.get(blocks).unwrap_or(&[])))
+    }
+    /// Iterate over all rows as borrowed [`Bitset`]s, see
+    /// [`JaggedBitset::get_row_bitset`].
+    ///
+    /// # Panics
+    /// If this `JaggedBitset` wasn't built with [`Builder::aligned`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::{jagged_bitset, Bitset};
+    ///
+    /// let jagged = jagged_bitset::Builder::aligned(3)
+    ///     .with_row([0, 2, 33])
+    ///     .with_row([])
+    ///     .with_row([2, 3, 33])
+    ///     .build();
+    ///
+    /// let mut common = Bitset(vec![u32::MAX; 2]);
+    /// for row in jagged.rows() {
+    ///     if row.bit_len() != 0 {
+    ///         common.and_with(&row);
+    ///     }
+    /// }
+    /// assert_eq!(common.ones().collect::<Vec<_>>(), [2, 33]);
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = Bitset<&[u32]>> + '_ {
+        assert!(
+            self.aligned,
+            "JaggedBitset::rows requires a bitset built with `Builder::aligned`"
+        );
+        (0..self.height()).filter_map(|i| self.get_row_bitset(i))
+    }
 
     /// Like [`JaggedBitset::braille_display`], but with rows and columns
     /// transposed (ie: rotated 90º clockwise and mirrored).
@@ -155,7 +279,7 @@ impl JaggedBitset {
     /// assert_eq!(expected, &shown);
     /// ```
     #[must_use]
-    pub const fn braille_trans_display(&self) -> BrailleTransposedDisplay {
+    pub const fn braille_trans_display(&self) -> BrailleTransposedDisplay<'_> {
         BrailleTransposedDisplay { bitset: self }
     }
     /// Return a struct that, when printed with [`fmt::Display`] or [`fmt::Debug`],
@@ -180,7 +304,7 @@ impl JaggedBitset {
     ///
     /// [wikipedia]: https://en.wikipedia.org/wiki/Braille_Patterns
     #[must_use]
-    pub const fn braille_display(&self) -> BrailleDisplay {
+    pub const fn braille_display(&self) -> BrailleDisplay<'_> {
         BrailleDisplay { bitset: self }
     }
 }
@@ -188,10 +312,20 @@ impl JaggedBitset {
 ///
 /// [`JaggedBitset`] is immutable with a fixed capacity, so it is necessary
 /// to pass through a builder ot create one.
+///
+/// Rows are packed tightly: they are not aligned to `u32` blocks, and the
+/// bit length of a row is one past its last enabled bit. Trailing disabled
+/// bits of a row are therefore not preserved. Use [`JaggedBitset::width`]
+/// to get the bit length of a row.
+///
+/// Use [`Builder::aligned`] to start each row at a `u32` block boundary
+/// instead. This wastes up to 31 bits per row, but lets you borrow rows as
+/// [`Bitset`]s with [`JaggedBitset::rows`].
 #[derive(Debug, Clone, Default)]
 pub struct Builder {
     ends: Vec<u32>,
     bits: Bitset<Vec<u32>>,
+    aligned: bool,
 }
 impl Builder {
     /// Initialize a [`Builder`].
@@ -205,14 +339,27 @@ impl Builder {
         Builder {
             ends: Vec::with_capacity(cap),
             bits: Bitset(Vec::new()),
+            aligned: false,
         }
     }
+    /// Initialize a [`Builder`] with capacity rows, where each row starts
+    /// at a `u32` block boundary.
+    ///
+    /// The built [`JaggedBitset`] uses more memory, up to 31 padding bits per
+    /// row, but its rows can be borrowed as [`Bitset`]s, see
+    /// [`JaggedBitset::rows`]. [`JaggedBitset::width`] doesn't include
+    /// padding bits.
+    #[must_use]
+    pub fn aligned(cap: usize) -> Self {
+        Builder { aligned: true, ..Self::with_capacity(cap) }
+    }
     /// Create the immutable [`JaggedBitset`], consuming this constructor.
     #[must_use]
     pub fn build(&mut self) -> JaggedBitset {
         JaggedBitset {
             ends: self.ends.drain(..).enumerate().collect(),
             bits: Bitset(std::mem::take(&mut self.bits.0).into_boxed_slice()),
+            aligned: self.aligned,
         }
     }
     /// Add a single row to this [`Builder`], returning it.
@@ -234,22 +381,59 @@ impl Builder {
     /// ```
     pub fn with_row(&mut self, row: impl IntoIterator<Item = u32>) -> &mut Self {
         let start = self.ends.last().map_or(0, |i| *i);
+        let start = if self.aligned { align_block(start) } else { start };
 
         let mut row_len = 0;
         for bit in row {
-            let bit_len = self.bits.bit_len();
-            let bit_in_array = (bit + start) as usize;
-
-            if bit_len <= bit_in_array {
-                let extra_blocks = (bit_in_array - bit_len) / mem::size_of::<u32>() + 1;
-                self.bits.0.extend(iter::repeat(0).take(extra_blocks));
-            }
-            self.bits.enable_bit(bit_in_array);
+            self.bits.enable_bit_extending((bit + start) as usize);
             row_len = row_len.max(bit + 1);
         }
         self.ends.push(start + row_len);
         self
     }
+    /// Add a single row to this [`Builder`], where `bits` are the indices of
+    /// enabled bits in the row.
+    ///
+    /// Same as [`Builder::with_row`], but with `usize` indices, which is
+    /// more convenient when converting from other bitsets.
+    ///
+    /// # Panics
+    ///
+    /// If a bit index in `bits` doesn't fit in a `u32`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::{jagged_bitset, Bitset};
+    ///
+    /// let rows: Vec<Bitset<Box<[u32]>>> = vec![
+    ///     [0_usize, 3, 40].into_iter().collect(),
+    ///     Bitset(Box::new([])),
+    ///     [1_usize].into_iter().collect(),
+    /// ];
+    /// let mut builder = jagged_bitset::Builder::new();
+    /// for row in &rows {
+    ///     builder.add_row(row.ones().map(|bit| bit as usize));
+    /// }
+    /// let jagged = builder.build();
+    ///
+    /// assert_eq!(jagged.row(0).collect::<Vec<_>>(), [0, 3, 40]);
+    /// assert_eq!(jagged.width(0), 41);
+    /// assert_eq!(jagged.width(1), 0);
+    /// assert_eq!(jagged.row(2).collect::<Vec<_>>(), [1]);
+    /// ```
+    pub fn add_row(&mut self, bits: impl IntoIterator<Item = usize>) -> &mut Self {
+        self.with_row(bits.into_iter().map(|bit| {
+            u32::try_from(bit).expect("bit index too large for JaggedBitset, must fit in a u32")
+        }))
+    }
+}
+
+const BLOCK_BITS: usize = u32::BITS as usize;
+
+/// Round `bit` up to the next `u32` block boundary.
+const fn align_block(bit: u32) -> u32 {
+    bit.next_multiple_of(u32::BITS)
 }
 
 fn display_braille(
@@ -318,3 +502,15 @@ impl<'a> fmt::Display for BrailleDisplay<'a> {
         display_braille(f, height, width, |x, y| u32::from(self.bitset.bit(x, y)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    #[should_panic(expected = "bit index too large")]
+    fn add_row_past_u32() {
+        Builder::new().add_row([0, u32::MAX as usize + 1]);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! [jagged array]: https://en.wikipedia.org/wiki/Jagged_array
 
-use std::{fmt, marker::PhantomData, mem::ManuallyDrop};
+use std::{fmt, iter, marker::PhantomData, mem, mem::ManuallyDrop};
 
 use thiserror::Error;
 
@@ -14,7 +14,7 @@ pub enum Error {
     #[error(
         "Cannot build JaggedVec: `ends` represents the end of each row in `data`, \
         it should be monotonically increasing. \
-        Found `end` at position {i} lower than `end` at position {}", .i - 1
+        Found `end` at position {i} lower than the `end` preceding it"
     )]
     BadEnd { i: usize },
     /// An `end` in `ends` was too large.
@@ -146,6 +146,42 @@ impl<T> JaggedVec<T> {
         self.fully_popped = false;
         self.data.extend(elems);
     }
+    /// Reserve capacity for at least `rows` more rows and `cells` more cells.
+    ///
+    /// This doesn't add any rows, see [`JaggedVec::with_capacity`].
+    pub fn reserve(&mut self, rows: usize, cells: usize) {
+        self.ends.reserve(rows);
+        self.data.reserve(cells);
+    }
+    /// Move all rows of `other` at the end of `self`, leaving `other` empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use datazoo::JaggedVec;
+    ///
+    /// let mut jagged = JaggedVec::empty();
+    /// jagged.push_row([0, 1]).push_row([2]);
+    ///
+    /// let mut other = JaggedVec::empty();
+    /// other.push_row([]).push_row([3, 4]);
+    ///
+    /// jagged.append(&mut other);
+    /// assert_eq!(other.height(), 0);
+    /// assert_eq!(jagged.into_vecs(), vec![vec![0, 1], vec![2], vec![], vec![3, 4]]);
+    /// ```
+    pub fn append(&mut self, other: &mut JaggedVec<T>) {
+        if other.fully_popped {
+            return;
+        }
+        let offset = self.data.len() as u32;
+        if !self.fully_popped {
+            self.ends.push(offset);
+        }
+        self.ends.extend(other.ends.iter().map(|end| end + offset));
+        self.data.append(&mut other.data);
+        self.fully_popped = false;
+        other.clear();
+    }
     /// Remove all rows from this `JaggedVec`.
     pub fn clear(&mut self) {
         self.fully_popped = true;
@@ -178,7 +214,7 @@ impl<T> JaggedVec<T> {
     /// jagged.pop_row();
     /// assert_eq!(jagged.height(), 0);
     /// ```
-    pub fn pop_row(&mut self) -> Option<PoppedRow<T>> {
+    pub fn pop_row(&mut self) -> Option<PoppedRow<'_, T>> {
         if self.fully_popped {
             return None;
         }
@@ -227,6 +263,29 @@ impl<T> JaggedVec<T> {
             fully_popped: true,
         }
     }
+    /// Create an empty `JaggedVec` with capacity for at least `rows` rows
+    /// and `cells` cells.
+    ///
+    /// Like [`JaggedVec::empty`], the returned `JaggedVec` has no rows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use datazoo::JaggedVec;
+    ///
+    /// let mut jagged = JaggedVec::with_capacity(3, 10);
+    /// assert_eq!(jagged.height(), 0);
+    ///
+    /// jagged.push_row([0, 1, 2]).push_row([]).push_row([3]);
+    /// assert_eq!(jagged.into_vecs(), vec![vec![0, 1, 2], vec![], vec![3]]);
+    /// ```
+    #[must_use]
+    pub fn with_capacity(rows: usize, cells: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(cells),
+            ends: Vec::with_capacity(rows),
+            fully_popped: true,
+        }
+    }
     /// Create a [`JaggedVec`] of `ends.len() + 1` rows, values of `ends` are the
     /// end indicies (exclusive) of each row in `data`.
     ///
@@ -314,6 +373,105 @@ impl<T> JaggedVec<T> {
         // SAFETY: We always push ends that are smaller that data.len() to self.end
         Some(unsafe { self.data.get_unchecked(start..end) })
     }
+    /// Swap the rows at index `a` and `b`.
+    ///
+    /// Elements between the two rows are moved if the two rows have a different
+    /// length, but the buffers are not reallocated. Swapping a row with
+    /// itself does nothing.
+    ///
+    /// # Panics
+    /// When either `a` or `b` is out of bound (`>= self.height()`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::JaggedVec;
+    ///
+    /// let mut jagged = JaggedVec::empty();
+    /// jagged.push_row([0, 1, 2]).push_row([3]).push_row([]).push_row([4, 5]);
+    ///
+    /// jagged.swap_rows(0, 3);
+    /// assert_eq!(jagged.clone().into_vecs(), vec![vec![4, 5], vec![3], vec![], vec![0, 1, 2]]);
+    ///
+    /// jagged.swap_rows(2, 1);
+    /// assert_eq!(jagged.into_vecs(), vec![vec![4, 5], vec![], vec![3], vec![0, 1, 2]]);
+    /// ```
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        let height = self.height();
+        assert!(
+            a < height && b < height,
+            "swap_rows({a}, {b}) out of bound: height is {height}"
+        );
+
+        let (a, b) = (a.min(b), a.max(b));
+        if a == b {
+            return;
+        }
+        let start = |i: usize| i.checked_sub(1).map_or(0, |i| self.ends[i]) as usize;
+        let end = |i: usize| self.ends.get(i).map_or(self.data.len(), |e| *e as usize);
+        let (a_start, a_len) = (start(a), end(a) - start(a));
+        let (b_end, b_len) = (end(b), end(b) - start(b));
+
+        // window is [A, M, B] with M the rows between `a` and `b`.
+        let window = &mut self.data[a_start..b_end];
+        let m_len = window.len() - a_len - b_len;
+        // [M, B, A]
+        window.rotate_left(a_len);
+        // [B, M, A]
+        window[..m_len + b_len].rotate_left(m_len);
+
+        let (a_len, b_len) = (a_len as u32, b_len as u32);
+        self.ends[a] = a_start as u32 + b_len;
+        for end in &mut self.ends[a + 1..b] {
+            *end = *end - a_len + b_len;
+        }
+    }
+    /// Keep only the rows for which `f(row_index, row)` returns `true`,
+    /// dropping the elements of removed rows.
+    ///
+    /// Rows are visited in order, each exactly once. Unlike removing rows one
+    /// by one, elements are moved at most once.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::JaggedVec;
+    ///
+    /// let mut jagged = JaggedVec::empty();
+    /// jagged.push_row([0, 1, 2]).push_row([3]).push_row([]).push_row([4, 5]);
+    ///
+    /// jagged.retain_rows(|i, row| i != 0 && !row.is_empty());
+    /// assert_eq!(jagged.clone().into_vecs(), vec![vec![3], vec![4, 5]]);
+    ///
+    /// jagged.retain_rows(|_, _| false);
+    /// assert_eq!(jagged.height(), 0);
+    /// ```
+    pub fn retain_rows(&mut self, mut f: impl FnMut(usize, &[T]) -> bool) {
+        let height = self.height();
+        let mut keep = Vec::with_capacity(height);
+        let mut new_ends = Vec::with_capacity(height);
+        let mut kept_len = 0;
+        for (i, row) in self.rows().enumerate() {
+            let kept = f(i, row);
+            if kept {
+                kept_len += row.len() as u32;
+                new_ends.push(kept_len);
+            }
+            keep.push(kept);
+        }
+        let ends = &self.ends;
+        let (mut row, mut index) = (0, 0);
+        self.data.retain(|_| {
+            while ends.get(row).is_some_and(|end| index >= *end as usize) {
+                row += 1;
+            }
+            index += 1;
+            keep[row]
+        });
+        // The end of the last row is `data.len()`, it isn't stored in `ends`.
+        self.fully_popped = new_ends.pop().is_none();
+        self.ends = new_ends;
+    }
     /// Get `V` at exact `direct_index` ignoring row sizes,
     /// acts as if the whole array was a single row.
     ///
@@ -349,24 +507,52 @@ impl<T> JaggedVec<T> {
             return Vec::new();
         }
         let mut iliffe = Vec::with_capacity(ends.len() + 1);
-        let mut last_end = 0;
 
-        // TODO(perf): this is slow as heck because each drain needs to move
-        // forward the end of the `data` vec, if we reverse ends here, we can
-        // skip the nonsense.
-        for end in ends {
-            let size = (end - last_end) as usize;
-            iliffe.push(data.drain(..size).collect());
-            last_end = end;
+        // Split rows from the back, so that we never move the tail of `data`.
+        for end in ends.into_iter().rev() {
+            iliffe.push(data.split_off(end as usize));
         }
-        // the last row.
+        // `data` is now the first row, drop the capacity of the split off rows.
+        data.shrink_to_fit();
         iliffe.push(data);
+        iliffe.reverse();
         iliffe
     }
     /// Iterate over all the rows in the `JaggedVec`.
     pub fn rows(&self) -> impl Iterator<Item = &[T]> {
         (0..self.height()).map(|i| unsafe { self.get_row(i).unwrap_unchecked() })
     }
+    /// Iterate mutably over all the rows in the `JaggedVec`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use datazoo::JaggedVec;
+    ///
+    /// let mut jagged = JaggedVec::empty();
+    /// jagged.push_row([2, 0, 1]).push_row([]).push_row([5, 3]);
+    ///
+    /// for row in jagged.rows_mut() {
+    ///     row.sort_unstable();
+    /// }
+    /// assert_eq!(jagged.into_vecs(), vec![vec![0, 1, 2], vec![], vec![3, 5]]);
+    /// ```
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let height = self.height();
+        let data_len = self.data.len();
+        let mut remaining = self.data.as_mut_slice();
+        let mut start = 0;
+
+        let ends = self.ends.iter().map(|end| *end as usize);
+        ends.chain(iter::once(data_len))
+            .take(height)
+            .map(move |end| {
+                let (row, rest) = mem::take(&mut remaining).split_at_mut(end - start);
+                remaining = rest;
+                start = end;
+                row
+            })
+    }
 }
 impl<T: fmt::Debug> fmt::Debug for JaggedVec<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -414,4 +600,104 @@ mod test {
         drop(jagged);
         assert_eq!(count.load(Ordering::Relaxed), 0);
     }
+    #[test]
+    fn retain_rows_drops() {
+        let count = AtomicI64::new(0);
+        let mk_ref = || RefCount::new(&count);
+        let mut jagged = JaggedVec::empty();
+        jagged
+            .push_row([mk_ref(), mk_ref()])
+            .push_row([])
+            .push_row([mk_ref(), mk_ref(), mk_ref()])
+            .push_row([mk_ref()]);
+        assert_eq!(count.load(Ordering::Relaxed), 6);
+
+        jagged.retain_rows(|i, _| i != 2);
+        assert_eq!(count.load(Ordering::Relaxed), 3);
+        assert_eq!(jagged.height(), 3);
+        let lens: Vec<_> = jagged.rows().map(<[_]>::len).collect();
+        assert_eq!(lens, [2, 0, 1]);
+
+        jagged.retain_rows(|_, row| row.is_empty());
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        assert_eq!(jagged.height(), 1);
+
+        jagged.retain_rows(|_, _| false);
+        assert_eq!(jagged.height(), 0);
+        jagged.push_row([mk_ref()]);
+        assert_eq!(jagged.height(), 1);
+        drop(jagged);
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+    }
+    #[test]
+    fn retain_rows() {
+        let rows: Vec<Vec<u32>> = (0..12)
+            .map(|i| (0..i % 4).map(|j| i * 10 + j).collect())
+            .collect();
+        for modulo in 1..5 {
+            let mut jagged = JaggedVec::empty();
+            for row in &rows {
+                jagged.push_row(row.iter().copied());
+            }
+            jagged.retain_rows(|i, _| i % modulo == 1);
+
+            let expected: Vec<_> = (0..rows.len())
+                .filter(|i| i % modulo == 1)
+                .map(|i| rows[i].clone())
+                .collect();
+            assert_eq!(jagged.into_vecs(), expected, "modulo {modulo}");
+        }
+    }
+    #[test]
+    fn into_vecs_many_rows() {
+        let expected: Vec<Vec<u32>> = (0..1000).map(|i| (0..i % 7).collect()).collect();
+        let mut jagged = JaggedVec::empty();
+        for row in &expected {
+            jagged.push_row(row.iter().copied());
+        }
+        let vecs = jagged.into_vecs();
+        assert_eq!(vecs, expected);
+        assert!(vecs.iter().all(|row| row.capacity() == row.len()));
+    }
+    #[test]
+    fn swap_rows() {
+        let rows: Vec<Vec<u32>> = (0..12)
+            .map(|i| (0..i % 4).map(|j| i * 10 + j).collect())
+            .collect();
+        for a in 0..rows.len() {
+            for b in 0..rows.len() {
+                let mut jagged = JaggedVec::empty();
+                for row in &rows {
+                    jagged.push_row(row.iter().copied());
+                }
+                jagged.swap_rows(a, b);
+
+                let mut expected = rows.clone();
+                expected.swap(a, b);
+                assert_eq!(jagged.into_vecs(), expected, "swap_rows({a}, {b})");
+            }
+        }
+    }
+    #[test]
+    fn append() {
+        let mut other = JaggedVec::empty();
+        other.push_row([]).push_row([1, 2]).push_row([]);
+        let expected = other.clone();
+
+        let mut empty = JaggedVec::empty();
+        empty.append(&mut other);
+        assert_eq!(empty, expected);
+        assert_eq!(other, JaggedVec::empty());
+
+        let mut one_row = JaggedVec::empty();
+        one_row.push_row([0]);
+        one_row.append(&mut JaggedVec::empty());
+        assert_eq!(one_row.clone().into_vecs(), vec![vec![0]]);
+
+        one_row.append(&mut expected.clone());
+        assert_eq!(
+            one_row.into_vecs(),
+            vec![vec![0], vec![], vec![1, 2], vec![]]
+        );
+    }
 }
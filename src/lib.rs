@@ -5,8 +5,14 @@
     clippy::module_name_repetitions
 )]
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub use bimultimap::Bimultimap;
+#[cfg(feature = "std")]
 pub use bitmatrix::BitMatrix;
 pub use bitset::Bitset;
 #[cfg(feature = "enumset")]
@@ -14,18 +20,29 @@ pub use enum_bitmatrix::EnumBitMatrix;
 #[cfg(feature = "enumset")]
 pub use enum_multimap::EnumMultimap;
 pub use index::Index;
+#[cfg(feature = "std")]
 pub use index_multimap::IndexMultimap;
+#[cfg(feature = "std")]
 pub use jagged_array::JaggedArray;
+#[cfg(feature = "std")]
 pub use jagged_bitset::JaggedBitset;
+#[cfg(feature = "std")]
 pub use jagged_vec::JaggedVec;
+#[cfg(feature = "std")]
 pub use packed_int_array::PackedIntArray;
+#[cfg(feature = "std")]
+pub use sorted::{SortedIteratorExt, SortedPairIteratorExt};
+#[cfg(feature = "std")]
 pub use sorted_iter::assume::{AssumeSortedByItemExt, AssumeSortedByKeyExt};
+#[cfg(feature = "std")]
 pub use sorted_iter::{
     sorted_iterator::SortedByItem, sorted_pair_iterator::SortedByKey, SortedIterator,
     SortedPairIterator,
 };
 
+#[cfg(feature = "std")]
 pub mod bimultimap;
+#[cfg(feature = "std")]
 pub mod bitmatrix;
 pub mod bitset;
 #[cfg(feature = "enumset")]
@@ -33,16 +50,22 @@ pub mod enum_bitmatrix;
 #[cfg(feature = "enumset")]
 pub mod enum_multimap;
 // pub mod index_map;
+#[cfg(feature = "std")]
 pub mod index_multimap;
+#[cfg(feature = "std")]
 pub mod jagged_array;
+#[cfg(feature = "std")]
 pub mod jagged_bitset;
+#[cfg(feature = "std")]
 pub mod jagged_vec;
+#[cfg(feature = "std")]
 pub mod packed_int_array;
+#[cfg(feature = "std")]
 pub mod sorted;
 
 /// Integer division rounded up.
 const fn div_ceil(lhf: usize, rhs: usize) -> usize {
-    (lhf + rhs - 1) / rhs
+    lhf.div_ceil(rhs)
 }
 const fn safe_n_mask(n: u32) -> u32 {
     // https://stackoverflow.com/questions/52573447/creating-a-mask-with-n-least-significant-bits-set
@@ -51,14 +74,23 @@ const fn safe_n_mask(n: u32) -> u32 {
         n => (1 << n) - 1,
     }
 }
+#[cfg(feature = "std")]
 trait MostSignificantBit {
     fn most_significant_bit(&self) -> u32;
 }
+#[cfg(feature = "std")]
 impl MostSignificantBit for u32 {
     fn most_significant_bit(&self) -> u32 {
         u32::BITS - self.leading_zeros()
     }
 }
+#[cfg(feature = "std")]
+impl MostSignificantBit for u64 {
+    fn most_significant_bit(&self) -> u32 {
+        u64::BITS - self.leading_zeros()
+    }
+}
+#[cfg(feature = "std")]
 impl MostSignificantBit for usize {
     fn most_significant_bit(&self) -> u32 {
         usize::BITS - self.leading_zeros()
@@ -69,9 +101,33 @@ impl MostSignificantBit for usize {
 #[rustfmt::skip]
 #[allow(clippy::inline_always, clippy::unnecessary_cast)] // I mean, have you _seen_ what is being inlined?
 mod index {
+    use core::num::{NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize};
+
     /// A type that can be cast into an index.
     ///
     /// Note that `Index` types are assumed to **NOT** have a significant drop.
+    ///
+    /// # Panics
+    ///
+    /// `Index` is implemented for `NonZero*` types, such as `NonZeroU32`.
+    /// For those, [`Index::new`] panics when `v` is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use datazoo::Index;
+    ///
+    /// let four = NonZeroU32::new(4).unwrap();
+    /// assert_eq!(Index::get(&four), 4);
+    /// assert_eq!(<NonZeroU32 as Index>::new(4), four);
+    /// ```
+    /// ```should_panic
+    /// use std::num::NonZeroU32;
+    /// use datazoo::Index;
+    ///
+    /// let _ = <NonZeroU32 as Index>::new(0);
+    /// ```
     pub trait Index {
         /// Get the index value of this type.
         fn get(&self) -> usize;
@@ -83,9 +139,16 @@ mod index {
     impl Index for u16   { #[inline(always)] fn get(&self) -> usize { *self as usize }#[inline(always)] fn new(v: usize) -> Self { v as Self } }
     impl Index for u32   { #[inline(always)] fn get(&self) -> usize { *self as usize }#[inline(always)] fn new(v: usize) -> Self { v as Self } }
     impl Index for u64   { #[inline(always)] fn get(&self) -> usize { *self as usize }#[inline(always)] fn new(v: usize) -> Self { v as Self } }
+
+    const ZERO_MSG: &str = "Attempted to create a NonZero Index from 0";
+    impl Index for NonZeroUsize { #[inline(always)] fn get(&self) -> usize { NonZeroUsize::get(*self) as usize }#[inline(always)] fn new(v: usize) -> Self { NonZeroUsize::new(v as usize).expect(ZERO_MSG) } }
+    impl Index for NonZeroU8    { #[inline(always)] fn get(&self) -> usize { NonZeroU8::get(*self) as usize }   #[inline(always)] fn new(v: usize) -> Self { NonZeroU8::new(v as u8).expect(ZERO_MSG) } }
+    impl Index for NonZeroU16   { #[inline(always)] fn get(&self) -> usize { NonZeroU16::get(*self) as usize }  #[inline(always)] fn new(v: usize) -> Self { NonZeroU16::new(v as u16).expect(ZERO_MSG) } }
+    impl Index for NonZeroU32   { #[inline(always)] fn get(&self) -> usize { NonZeroU32::get(*self) as usize }  #[inline(always)] fn new(v: usize) -> Self { NonZeroU32::new(v as u32).expect(ZERO_MSG) } }
+    impl Index for NonZeroU64   { #[inline(always)] fn get(&self) -> usize { NonZeroU64::get(*self) as usize }  #[inline(always)] fn new(v: usize) -> Self { NonZeroU64::new(v as u64).expect(ZERO_MSG) } }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
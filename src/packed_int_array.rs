@@ -2,9 +2,11 @@
 //!
 //! [associative array]: https://en.wikipedia.org/wiki/Associative_array
 
-use std::{fmt, marker::PhantomData};
+use std::{fmt, iter::Rev, marker::PhantomData, ops::Range};
 
-use crate::{div_ceil, safe_n_mask, Bitset, Index, MostSignificantBit};
+use sorted_iter::sorted_pair_iterator::SortedByKey;
+
+use crate::{div_ceil, Bitset, Index, MostSignificantBit};
 
 /// Parametrize [`PackedIntArray`] to implement equality in terms of `V` rather
 /// than raw bit value.
@@ -22,7 +24,7 @@ use crate::{div_ceil, safe_n_mask, Bitset, Index, MostSignificantBit};
 ///
 /// #[derive(Debug, Clone, PartialEq)]
 /// struct MyV(u32);
-/// impl From<u32> for MyV { fn from(v: u32) -> Self {MyV(v)} }
+/// impl TryFrom<u64> for MyV { type Error = (); fn try_from(v: u64) -> Result<Self, ()> { u32::try_from(v).map(MyV).map_err(drop) } }
 /// impl Index for MyV { fn get(&self) -> usize {self.0 as usize} fn new(v: usize) -> Self { MyV(v as u32) } }
 ///
 /// let mut map = PackedIntArray::<usize, MyV, ValueEq>::with_capacity(32, 32);
@@ -159,7 +161,7 @@ pub enum ValueEq {}
 /// [`IndexMultimap`]: crate::IndexMultimap
 /// [associative array]: https://en.wikipedia.org/wiki/Associative_array
 #[derive(Clone)]
-pub struct PackedIntArray<K: Index, V: From<u32>, Eq = ()> {
+pub struct PackedIntArray<K: Index, V: TryFrom<u64>, Eq = ()> {
     /// A matrix of `max(K)` rows of `log₂(max(V) + 1)` bits, each row represents
     /// a single index.
     ///
@@ -172,7 +174,7 @@ pub struct PackedIntArray<K: Index, V: From<u32>, Eq = ()> {
     value_width: usize,
     _tys: PhantomData<fn(K, V, Eq)>,
 }
-impl<K: Index, V: From<u32>, Eq> Default for PackedIntArray<K, V, Eq> {
+impl<K: Index, V: TryFrom<u64>, Eq> Default for PackedIntArray<K, V, Eq> {
     fn default() -> Self {
         PackedIntArray {
             indices: Bitset(Vec::new().into_boxed_slice()),
@@ -181,7 +183,7 @@ impl<K: Index, V: From<u32>, Eq> Default for PackedIntArray<K, V, Eq> {
         }
     }
 }
-impl<K: Index, V: From<u32>, Eq> PackedIntArray<K, V, Eq> {
+impl<K: Index, V: TryFrom<u64>, Eq> PackedIntArray<K, V, Eq> {
     /// Initialize a [`PackedIntArray`] with static size.
     ///
     /// You can always insert:
@@ -209,6 +211,13 @@ impl<K: Index, V: From<u32>, Eq> PackedIntArray<K, V, Eq> {
     /// - `vwidth = ⌈log₂(max_value + 1)⌉` (width in bits of a value)
     #[must_use]
     pub fn with_capacity(key_len: usize, value_len: u32) -> Self {
+        Self::with_capacity_u64(key_len, u64::from(value_len))
+    }
+    /// Same as [`Self::with_capacity`], but accepts values wider than 32 bits.
+    ///
+    /// See [`Self::get_u64`] to read values wider than 32 bits.
+    #[must_use]
+    pub fn with_capacity_u64(key_len: usize, value_len: u64) -> Self {
         let vwidth = value_len.most_significant_bit() as usize;
         let bit_size = vwidth * key_len;
         let u32_size = div_ceil(bit_size, u32::BITS as usize);
@@ -218,6 +227,44 @@ impl<K: Index, V: From<u32>, Eq> PackedIntArray<K, V, Eq> {
             _tys: PhantomData,
         }
     }
+    /// Create a [`PackedIntArray`] where the value at key `k` is `values[k]`.
+    ///
+    /// Values are packed with the smallest width that can hold the largest
+    /// value in `values`. All keys in `0..values.len()` are occupied.
+    ///
+    /// # Panics
+    ///
+    /// If a value in `values` is `usize::MAX`. The all-ones value marks empty
+    /// slots, so it can't be stored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let values = [3_u32, 0, 12, 7, 7];
+    /// let map = PackedIntArray::<usize, u32>::from_values(&values);
+    ///
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), [(0, 3), (1, 0), (2, 12), (3, 7), (4, 7)]);
+    /// assert_eq!(map.get(&5), None);
+    /// ```
+    #[must_use]
+    pub fn from_values(values: &[V]) -> Self
+    where
+        V: Index,
+    {
+        let value_len = |v: &V| {
+            v.get()
+                .checked_add(1)
+                .expect("value too large for PackedIntArray, the all-ones value marks empty slots")
+        };
+        let max_value = values.iter().map(value_len).max().unwrap_or(0);
+        let mut map = PackedIntArray::with_capacity_u64(values.len(), max_value as u64);
+
+        for (key, value) in values.iter().enumerate() {
+            map.set(&K::new(key), value);
+        }
+        map
+    }
     /// How many keys at most this contains.
     ///
     /// Unlike a `HashMap`, the capacity also represents the upper
@@ -225,43 +272,256 @@ impl<K: Index, V: From<u32>, Eq> PackedIntArray<K, V, Eq> {
     ///
     /// This might not be the `key_len` provided as argument to [`Self::with_capacity`],
     /// as the underlying array aligns the number of bits to the next multiple of 32.
-    #[allow(clippy::unnecessary_lazy_evaluations)] // see comment
     #[must_use]
     pub fn capacity(&self) -> usize {
         let bit_len = self.indices.bit_len();
         // This prevents a division by zero when both bit_len and value_width are zero
-        (bit_len != 0)
-            .then(|| bit_len / self.value_width)
-            .unwrap_or(0)
+        if bit_len == 0 {
+            0
+        } else {
+            bit_len / self.value_width
+        }
+    }
+    /// The largest key that can be stored in this array, `None` if no keys
+    /// can be stored.
+    ///
+    /// This is `capacity() - 1`, it might be larger than the `key_len - 1`
+    /// provided to [`Self::with_capacity`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let mut map = PackedIntArray::<usize, u32>::with_capacity(10, 8);
+    /// let max_key = map.max_key().unwrap();
+    ///
+    /// assert!(max_key >= 9);
+    /// assert_eq!(map.set(&max_key, &7), Some(()));
+    /// assert_eq!(map.set(&(max_key + 1), &7), None);
+    ///
+    /// let empty = PackedIntArray::<usize, u32>::with_capacity(0, 8);
+    /// assert_eq!(empty.max_key(), None);
+    /// ```
+    #[must_use]
+    pub fn max_key(&self) -> Option<usize> {
+        self.capacity().checked_sub(1)
+    }
+    /// The width in bits of a single value in this array.
+    ///
+    /// Values up to `2^value_width - 2` can be stored, the all-ones value
+    /// marks empty slots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let map = PackedIntArray::<usize, u32>::with_capacity(10, 8);
+    /// assert_eq!(map.value_width(), 4);
+    /// ```
+    #[must_use]
+    pub const fn value_width(&self) -> u32 {
+        self.value_width as u32
     }
     #[inline]
     fn row_offset(&self, index: usize) -> usize {
         index.get() * self.value_width
     }
     #[inline]
-    fn value_mask(&self) -> Option<u32> {
+    fn value_mask(&self) -> Option<u64> {
         let shift = self.value_width as u32;
-        (shift != 0).then(|| safe_n_mask(shift))
+        (shift != 0).then(|| u64::MAX >> (u64::BITS - shift))
     }
-    fn get_index(&self, index: usize) -> Option<V> {
-        let offset = self.row_offset(index);
-        let width = self.value_width as u32;
-        let mask = self.value_mask()?;
-        let value = mask & self.indices.n_at(width, offset)?;
+    /// Whether values never cross a `u32` block boundary, ie: `value_width`
+    /// divides 32.
+    #[inline]
+    const fn fits_in_block(&self) -> bool {
+        self.value_width.is_power_of_two() && self.value_width <= u32::BITS as usize
+    }
+    /// Read the row starting at bit `offset`, `mask` is [`Self::value_mask`].
+    ///
+    /// This doesn't check that the row is within [`Self::capacity`].
+    #[inline]
+    fn read_row(&self, offset: usize, mask: u64) -> Option<u64> {
+        let value = if self.fits_in_block() {
+            let block = self.indices.0.get(offset / u32::BITS as usize)?;
+            mask & u64::from(block >> (offset % u32::BITS as usize))
+        } else {
+            mask & self.indices.n64_at(self.value_width as u32, offset)?
+        };
         // != means the row is not empty
-        (value != mask && index < self.capacity()).then(|| V::from(value))
+        (value != mask).then_some(value)
+    }
+    fn get_raw(&self, index: usize) -> Option<u64> {
+        let mask = self.value_mask()?;
+        if index >= self.capacity() {
+            return None;
+        }
+        self.read_row(self.row_offset(index), mask)
+    }
+    fn get_index(&self, index: usize) -> Option<V> {
+        self.get_raw(index).map(to_value)
     }
     /// Get the value associated with `index`, `None` if there isn't.
+    ///
+    /// # Panics
+    ///
+    /// If the stored value doesn't fit in `V` (ie: `V::try_from` fails).
+    /// This never happens when `V` is `u64`, or when values were inserted
+    /// with [`Self::set`]. Use [`Self::get_u64`] to read the raw value.
     #[inline]
     pub fn get(&self, index: &K) -> Option<V> {
         self.get_index(index.get())
     }
+    /// Get the values associated with each of `keys`, `None` for keys without
+    /// values.
+    ///
+    /// This is equivalent to calling [`Self::get`] on each key, but the value
+    /// mask and capacity are computed once for all keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let map: PackedIntArray<usize, u32> = [(0, 3), (2, 8), (5, 1)].into_iter().collect();
+    ///
+    /// assert_eq!(map.get_many([&5, &1, &0, &100]), [Some(1), None, Some(3), None]);
+    /// ```
+    pub fn get_many<const N: usize>(&self, keys: [&K; N]) -> [Option<V>; N] {
+        let capacity = self.capacity();
+        let mask = self.value_mask();
+        keys.map(|key| {
+            let index = key.get();
+            if index >= capacity {
+                return None;
+            }
+            let value = self.read_row(self.row_offset(index), mask?)?;
+            Some(to_value(value))
+        })
+    }
+    /// Iterate over the values associated with each key in `keys`, `None` for
+    /// keys without values.
+    ///
+    /// The iterator yields exactly `keys.len()` items, keys past
+    /// [`Self::capacity`] are `None`.
+    ///
+    /// This is faster than calling [`Self::get`] on each key, as consecutive
+    /// keys are at consecutive bit offsets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let map: PackedIntArray<usize, u32> = [(0, 3), (2, 8), (5, 1)].into_iter().collect();
+    ///
+    /// let values: Vec<_> = map.get_range(1..7).collect();
+    /// assert_eq!(values, [None, Some(8), None, None, Some(1), None]);
+    /// ```
+    pub fn get_range(&self, keys: Range<usize>) -> impl ExactSizeIterator<Item = Option<V>> + '_ {
+        let capacity = self.capacity();
+        let mask = self.value_mask();
+        let width = self.value_width;
+        let mut offset = keys.start.min(capacity) * width;
+        keys.map(move |index| {
+            if index >= capacity {
+                return None;
+            }
+            let row = offset;
+            offset += width;
+            let value = self.read_row(row, mask?)?;
+            Some(to_value(value))
+        })
+    }
+    /// Get the value associated with `key`, without checking that `key` is
+    /// within bounds or that there is a value associated with it.
+    ///
+    /// Like [`Self::get`], this panics if the stored value doesn't fit in `V`.
+    ///
+    /// # Safety
+    ///
+    /// `key` must be lower than [`Self::capacity`], and a value must be
+    /// associated with `key` (ie: `self.get(key)` returns `Some`).
+    /// This is checked in debug builds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let map: PackedIntArray<usize, u32> = [(0, 3), (20, 5)].into_iter().collect();
+    ///
+    /// // SAFETY: `20` was just inserted
+    /// assert_eq!(unsafe { map.get_unchecked(&20) }, 5);
+    /// ```
+    #[inline]
+    pub unsafe fn get_unchecked(&self, key: &K) -> V {
+        let index = key.get();
+        debug_assert!(
+            self.get_index(index).is_some(),
+            "PackedIntArray::get_unchecked called with an empty or out of bound key {index}"
+        );
+        let offset = self.row_offset(index);
+        let (block, shift) = (offset / u32::BITS as usize, offset % u32::BITS as usize);
+        let blocks = &self.indices.0;
+
+        // SAFETY: `index < self.capacity()`, so the first bit of its row is
+        // within `blocks`.
+        let first = u128::from(*unsafe { blocks.get_unchecked(block) });
+        let get = |i: usize| u128::from(blocks.get(block + i).map_or(0, |b| *b));
+        let window = first | get(1) << 32 | get(2) << 64;
+
+        let mask = u64::MAX >> (u64::BITS - self.value_width as u32);
+        to_value((window >> shift) as u64 & mask)
+    }
+    /// Get the value associated with `index` as a `u64`, `None` if there isn't.
+    ///
+    /// Unlike [`Self::get`], this works with values wider than 32 bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let mut map = PackedIntArray::<usize, u64>::with_capacity_u64(10, 1 << 48);
+    ///
+    /// map.set(&3, &0xffff_ffff_0000);
+    /// map.set(&7, &0xffff_ffff_0000);
+    /// map.set(&8, &12);
+    ///
+    /// assert_eq!(map.get_u64(&3), Some(0xffff_ffff_0000));
+    /// assert_eq!(map.get_u64(&7), Some(0xffff_ffff_0000));
+    /// assert_eq!(map.get_u64(&8), Some(12));
+    /// assert_eq!(map.get_u64(&4), None);
+    /// ```
+    #[inline]
+    pub fn get_u64(&self, index: &K) -> Option<u64> {
+        self.get_raw(index.get())
+    }
     /// Remove value associated with `key`. Afterward, calling `map.get(key)`
     /// will return `None`.
     pub fn remove(&mut self, key: &K) {
         let offset = self.row_offset(key.get());
         self.indices.extend(offset..offset + self.value_width);
     }
+    /// Remove all values for which `f(&key, value)` returns `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let mut map: PackedIntArray<usize, u32> = [(0, 3), (2, 8), (5, 1), (9, 4)].into_iter().collect();
+    ///
+    /// map.retain(|key, value| *key != 5 && value >= 3);
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), [(0, 3), (2, 8), (9, 4)]);
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&K, V) -> bool) {
+        for index in 0..self.capacity() {
+            let Some(value) = self.get_index(index) else {
+                continue;
+            };
+            let key = K::new(index);
+            if !f(&key, value) {
+                self.remove(&key);
+            }
+        }
+    }
     /// Set value of `key` to `value`.
     ///
     /// Returns `None` if either `value` or `key` is out of bound.
@@ -286,8 +546,9 @@ impl<K: Index, V: From<u32>, Eq> PackedIntArray<K, V, Eq> {
     where
         V: Index,
     {
-        let value = value.get() as u32;
-        let key = key.get();
+        self.set_raw(key.get(), value.get() as u64)
+    }
+    fn set_raw(&mut self, key: usize, value: u64) -> Option<()> {
         let mask = self.value_mask()?;
 
         // either max value or larger than bitmask
@@ -296,10 +557,17 @@ impl<K: Index, V: From<u32>, Eq> PackedIntArray<K, V, Eq> {
         }
         let offset = self.row_offset(key);
 
+        if self.fits_in_block() {
+            let (block, shift) = (offset / u32::BITS as usize, offset % u32::BITS as usize);
+            let block = &mut self.indices.0[block];
+            *block = (*block & !((mask as u32) << shift)) | ((value as u32) << shift);
+            return Some(());
+        }
         self.indices
             .disable_range(offset..offset + self.value_width);
+        let value = [value as u32, (value >> u32::BITS) as u32];
         self.indices
-            .extend(Bitset([value]).ones().map(|v| v + offset as u32));
+            .extend(Bitset(value).ones().map(|v| v + offset as u32));
         Some(())
     }
     /// Set value of `key` to `value`.
@@ -311,32 +579,117 @@ impl<K: Index, V: From<u32>, Eq> PackedIntArray<K, V, Eq> {
     where
         V: Index,
     {
-        let value_u32 = value.get() as u32;
-        let value_bits = value_u32.most_significant_bit();
-        let width = self.value_width as u32;
-        if value_bits > width || value_u32 == self.value_mask()? {
-            let additional_bits = value_bits - width;
-            let offset = |x: u32| x + x / width * additional_bits;
-            let new_indices = self.indices.ones().map(offset);
-            self.indices = new_indices.collect();
-            self.value_width += additional_bits as usize;
+        let value_u64 = value.get() as u64;
+        // The all-ones value marks empty rows, so `value` must be lower than it.
+        let needed_width = value_u64.checked_add(1)?.most_significant_bit() as usize;
+        if needed_width > self.value_width {
+            let capacity = self.capacity();
+            let old = std::mem::replace(self, Self::with_capacity_u64(0, 0));
+            let bit_size = needed_width * capacity;
+            let u32_size = div_ceil(bit_size, u32::BITS as usize);
+            self.indices = Bitset(vec![u32::MAX; u32_size].into_boxed_slice());
+            self.value_width = needed_width;
+
+            for key in 0..capacity {
+                if let Some(value) = old.get_raw(key) {
+                    self.set_raw(key, value);
+                }
+            }
         }
         self.set(key, value)
     }
-    /// Iterate over all values.
+    /// Set value of `key` to `value`.
+    ///
+    /// Increase the number of keys if `key` is out of bound, new keys are empty.
+    /// If `value` is out of bound, does nothing and returns `None`, combine with
+    /// [`Self::set_expanding_values`] to also grow the value width.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let mut map = PackedIntArray::<usize, u32>::with_capacity(4, 16);
+    /// map.set(&1, &11);
+    /// assert_eq!(map.set(&40, &4), None);
+    ///
+    /// assert_eq!(map.set_expanding_keys(&40, &4), Some(()));
+    /// assert!(map.capacity() > 40);
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), [(1, 11), (40, 4)]);
+    ///
+    /// assert_eq!(map.set_expanding_keys(&50, &100), None);
+    /// ```
+    pub fn set_expanding_keys(&mut self, key: &K, value: &V) -> Option<()>
+    where
+        V: Index,
+    {
+        let key_len = key.get().checked_add(1)?;
+        if key_len > self.capacity() && self.value_width != 0 {
+            // Bits past the last key may be part of a new key, make sure it is empty.
+            let old_bit_len = self.indices.bit_len();
+            let unused_bits = self.capacity() * self.value_width..old_bit_len;
+
+            let u32_size = div_ceil(key_len * self.value_width, u32::BITS as usize);
+            let mut blocks = std::mem::take(&mut self.indices.0).into_vec();
+            blocks.resize(u32_size, u32::MAX);
+            self.indices = Bitset(blocks.into_boxed_slice());
+            self.indices.extend(unused_bits);
+        }
+        self.set(key, value)
+    }
+    /// Iterate over all values, in ascending key order.
+    ///
+    /// The returned iterator implements [`SortedByKey`], so it can be used
+    /// with [`SortedPairIterator`] methods such as `join`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// use datazoo::SortedPairIterator;
+    ///
+    /// let left: PackedIntArray<usize, u32> = [(1, 10), (3, 30), (4, 40)].into_iter().collect();
+    /// let right: PackedIntArray<usize, u32> = [(0, 0), (3, 3), (4, 4)].into_iter().collect();
+    ///
+    /// let joined: Vec<_> = left.iter().join(right.iter()).collect();
+    /// assert_eq!(joined, [(3, (30, 3)), (4, (40, 4))]);
+    /// ```
+    ///
+    /// [`SortedPairIterator`]: crate::SortedPairIterator
     #[inline]
-    pub fn iter(&self) -> impl Iterator<Item = (K, V)> + '_ {
-        (0..self.capacity()).filter_map(|k| self.get_index(k).map(|v| (K::new(k), v)))
+    pub fn iter(&self) -> Iter<'_, K, V, Eq> {
+        Iter { array: self, keys: 0..self.capacity() }
     }
     /// Iterate over all values (reversed).
     #[inline]
-    pub fn rev_iter(&self) -> impl Iterator<Item = (K, V)> + '_ {
-        (0..self.capacity())
-            .rev()
-            .filter_map(|k| self.get_index(k).map(|v| (K::new(k), v)))
+    pub fn rev_iter(&self) -> Rev<Iter<'_, K, V, Eq>> {
+        self.iter().rev()
+    }
+    /// Iterate over the raw packed value of every key in `0..capacity()`,
+    /// including empty ones.
+    ///
+    /// Unlike [`Self::iter`], empty keys are not skipped: They are
+    /// visible as the all-ones value `2^value_width - 1`.
+    ///
+    /// This is mostly useful to inspect the memory layout of the array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use datazoo::PackedIntArray;
+    /// let mut map = PackedIntArray::<usize, u32>::with_capacity(4, 3);
+    /// map.set(&1, &2);
+    /// map.set(&2, &0);
+    ///
+    /// assert_eq!(map.value_width(), 2);
+    /// let raw: Vec<_> = map.raw_rows().take(4).collect();
+    /// assert_eq!(raw, [0b11, 2, 0, 0b11]);
+    /// ```
+    pub fn raw_rows(&self) -> impl Iterator<Item = u64> + '_ {
+        let width = self.value_width as u32;
+        (0..self.capacity()).map_while(move |i| self.indices.n64_at(width, self.row_offset(i)))
     }
 }
-impl<K: Index, V: From<u32>> PartialEq for PackedIntArray<K, V> {
+impl<K: Index, V: TryFrom<u64>> PartialEq for PackedIntArray<K, V> {
     fn eq(&self, other: &Self) -> bool {
         let min_len = self.indices.0.len().min(other.indices.0.len());
         let largest = if self.indices.0.len() == min_len { other } else { self };
@@ -346,7 +699,7 @@ impl<K: Index, V: From<u32>> PartialEq for PackedIntArray<K, V> {
         common_identical && no_more
     }
 }
-impl<K: Index, V: From<u32> + PartialEq> PartialEq for PackedIntArray<K, V, ValueEq> {
+impl<K: Index, V: TryFrom<u64> + PartialEq> PartialEq for PackedIntArray<K, V, ValueEq> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         let max = self.capacity().max(other.capacity());
@@ -354,26 +707,42 @@ impl<K: Index, V: From<u32> + PartialEq> PartialEq for PackedIntArray<K, V, Valu
     }
 }
 
-impl<K: Index, V: From<u32> + Index> FromIterator<(K, V)> for PackedIntArray<K, V> {
+impl<K: Index, V: TryFrom<u64> + Index> FromIterator<(K, V)> for PackedIntArray<K, V> {
     /// Create a [`PackedIntArray`] where value at `k` will be `value` in `(key, value)`
     /// the last item where `key == k`.
     ///
+    /// An empty iterator creates an empty `PackedIntArray` with a capacity of `0`.
+    ///
     /// Note that all `K` and `V` will be dropped.
+    ///
+    /// # Panics
+    ///
+    /// If a key or a value is `usize::MAX`. The all-ones value marks empty
+    /// slots, so it can't be stored.
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        let mut max_value = 0;
-        let mut max_key = 0;
+        let mut max_value = 0_usize;
+        let mut max_key = 0_usize;
 
         let key_values = iter
             .into_iter()
             .map(|(k, v)| {
-                max_key = max_key.max(k.get() + 1);
-                max_value = max_value.max(v.get() + 1);
+                let key_len = k
+                    .get()
+                    .checked_add(1)
+                    .expect("key too large for PackedIntArray");
+                let value_len = v.get().checked_add(1).expect(
+                    "value too large for PackedIntArray, the all-ones value marks empty slots",
+                );
+                max_key = max_key.max(key_len);
+                max_value = max_value.max(value_len);
                 (k, v)
             })
             .collect::<Box<[_]>>();
 
-        let max_value = u32::try_from(max_value).unwrap();
-        let mut map = PackedIntArray::with_capacity(max_key, max_value);
+        if key_values.is_empty() {
+            return Self::default();
+        }
+        let mut map = PackedIntArray::with_capacity_u64(max_key, max_value as u64);
 
         for (key, value) in &*key_values {
             map.set(key, value);
@@ -381,10 +750,110 @@ impl<K: Index, V: From<u32> + Index> FromIterator<(K, V)> for PackedIntArray<K,
         map
     }
 }
+
+/// Convert a value read from a [`PackedIntArray`] into `V`.
+///
+/// # Panics
+///
+/// If `value` doesn't fit in `V`.
+#[inline]
+fn to_value<V: TryFrom<u64>>(value: u64) -> V {
+    let error = "value stored in PackedIntArray is too large for the value type";
+    V::try_from(value).ok().expect(error)
+}
+
+/// Iterator over the `(key, value)` pairs of a [`PackedIntArray`],
+/// see [`PackedIntArray::iter`].
+pub struct Iter<'a, K: Index, V: TryFrom<u64>, Eq> {
+    array: &'a PackedIntArray<K, V, Eq>,
+    keys: Range<usize>,
+}
+impl<K: Index, V: TryFrom<u64>, Eq> Clone for Iter<'_, K, V, Eq> {
+    fn clone(&self) -> Self {
+        Iter { array: self.array, keys: self.keys.clone() }
+    }
+}
+impl<K: Index, V: TryFrom<u64>, Eq> Iterator for Iter<'_, K, V, Eq> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let array = self.array;
+        self.keys
+            .find_map(|k| array.get_index(k).map(|v| (K::new(k), v)))
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.keys.len()))
+    }
+}
+impl<K: Index, V: TryFrom<u64>, Eq> DoubleEndedIterator for Iter<'_, K, V, Eq> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let array = self.array;
+        self.keys
+            .by_ref()
+            .rev()
+            .find_map(|k| array.get_index(k).map(|v| (K::new(k), v)))
+    }
+}
+impl<K: Index, V: TryFrom<u64>, Eq> SortedByKey for Iter<'_, K, V, Eq> {}
+
+/// Serializes the packed bits and the value width as-is, empty keys included.
+///
+/// Deserializing fails if the value width is larger than 64, or if it is `0`
+/// while the packed bits are not empty.
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use std::marker::PhantomData;
+
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::PackedIntArray;
+    use crate::{Bitset, Index};
+
+    #[derive(Serialize)]
+    #[serde(rename = "PackedIntArray")]
+    struct Ser<'a> {
+        value_width: u32,
+        indices: &'a [u32],
+    }
+    #[derive(Deserialize)]
+    #[serde(rename = "PackedIntArray")]
+    struct De {
+        value_width: u32,
+        indices: Box<[u32]>,
+    }
+    impl<K: Index, V: TryFrom<u64>, Eq> Serialize for PackedIntArray<K, V, Eq> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let value_width = self.value_width as u32;
+            Ser { value_width, indices: &self.indices.0 }.serialize(serializer)
+        }
+    }
+    impl<'de, K: Index, V: TryFrom<u64>, Eq> Deserialize<'de> for PackedIntArray<K, V, Eq> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let De { value_width, indices } = De::deserialize(deserializer)?;
+            if value_width > u64::BITS {
+                let msg = format!("value_width is {value_width}, it should be at most 64");
+                return Err(de::Error::custom(msg));
+            }
+            if value_width == 0 && !indices.is_empty() {
+                return Err(de::Error::custom(
+                    "value_width is 0, but indices is not empty",
+                ));
+            }
+            Ok(PackedIntArray {
+                indices: Bitset(indices),
+                value_width: value_width as usize,
+                _tys: PhantomData,
+            })
+        }
+    }
+}
 impl<K, V, Eq> fmt::Debug for PackedIntArray<K, V, Eq>
 where
     K: Index + fmt::Debug,
-    V: From<u32> + fmt::Debug,
+    V: TryFrom<u64> + fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut list = f.debug_map();
@@ -397,6 +866,8 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::iter;
+
     use super::*;
 
     #[test]
@@ -494,4 +965,224 @@ mod tests {
         assert_eq!(map.get(&35), Some(200));
         assert_eq!(map.get(&36), Some(1845));
     }
+    #[test]
+    fn set_expanding_keys() {
+        // value width 3, 10 keys per block, the 2 last bits of each block are unused.
+        let mut map = PackedIntArray::<usize, u32>::with_capacity(10, 7);
+        assert_eq!(map.capacity(), 10);
+        for key in 0..10 {
+            assert_eq!(map.set(&key, &(key as u32 % 7)), Some(()));
+        }
+        // Clear the unused bits, they shouldn't leak into key 10.
+        map.indices.disable_range(30..32);
+
+        assert_eq!(map.set_expanding_keys(&10, &0), Some(()));
+        assert_eq!(map.set_expanding_keys(&11, &7), None);
+        assert_eq!(map.get(&11), None);
+        assert_eq!(map.set_expanding_keys(&100, &6), Some(()));
+        assert!(map.capacity() > 100);
+
+        for key in 0..10 {
+            assert_eq!(map.get(&key), Some(key as u32 % 7));
+        }
+        assert_eq!(map.get(&10), Some(0));
+        assert_eq!(map.iter().skip(11).collect::<Vec<_>>(), [(100, 6)]);
+
+        let mut map = PackedIntArray::<usize, u32>::with_capacity(0, 0);
+        assert_eq!(map.set_expanding_keys(&3, &0), None);
+        assert_eq!(map.capacity(), 0);
+    }
+    #[test]
+    fn from_iter_empty() {
+        let map: PackedIntArray<usize, u32> = iter::empty().collect();
+
+        assert_eq!(map.capacity(), 0);
+        assert_eq!(map.max_key(), None);
+        assert_eq!(map.get(&0), None);
+        assert_eq!(map.iter().count(), 0);
+    }
+    #[test]
+    fn from_iter_max_u32() {
+        let map: PackedIntArray<usize, u32> = [(3, u32::MAX)].into_iter().collect();
+
+        assert_eq!(map.value_width(), 33);
+        assert_eq!(map.get(&3), Some(u32::MAX));
+        assert_eq!(map.get_u64(&3), Some(u64::from(u32::MAX)));
+        assert_eq!(map.get(&2), None);
+        assert_eq!(map.iter().collect::<Vec<_>>(), [(3, u32::MAX)]);
+    }
+    #[test]
+    #[should_panic(expected = "value too large")]
+    fn from_iter_max_usize() {
+        let _: PackedIntArray<usize, u64> = [(3, u64::MAX)].into_iter().collect();
+    }
+    #[test]
+    #[should_panic(expected = "value too large")]
+    fn from_values_max_usize() {
+        let _ = PackedIntArray::<usize, u64>::from_values(&[3, u64::MAX]);
+    }
+    #[test]
+    fn wide_values_as_v() {
+        let mut map = PackedIntArray::<usize, u64>::with_capacity_u64(10, 1 << 48);
+        map.set(&3, &0xffff_ffff_0000).unwrap();
+        map.set(&7, &0x1_0000_0001).unwrap();
+
+        assert_eq!(map.get(&3), Some(0xffff_ffff_0000));
+        assert_eq!(
+            map.get_many([&7, &3]),
+            [Some(0x1_0000_0001), Some(0xffff_ffff_0000)]
+        );
+        assert_eq!(unsafe { map.get_unchecked(&7) }, 0x1_0000_0001);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(3, 0xffff_ffff_0000), (7, 0x1_0000_0001)]
+        );
+        assert_eq!(
+            format!("{map:?}"),
+            format!("{{3: {}, 7: {}}}", 0xffff_ffff_0000_u64, 0x1_0000_0001_u64)
+        );
+
+        let mut value_eq = PackedIntArray::<usize, u64, ValueEq>::with_capacity_u64(10, 1 << 48);
+        let mut other = value_eq.clone();
+        value_eq.set(&3, &0xffff_ffff_0000).unwrap();
+        other.set(&3, &0x0000_ffff_0000).unwrap();
+        assert!(value_eq != other);
+    }
+    #[test]
+    #[should_panic(expected = "too large for the value type")]
+    fn wide_value_too_large_for_v() {
+        let mut map = PackedIntArray::<usize, u32>::with_capacity_u64(10, 1 << 48);
+        map.set_raw(3, 0xffff_ffff_0000).unwrap();
+        let _ = map.get(&3);
+    }
+    #[test]
+    fn wide_values() {
+        let wide = 0xffff_ffff_0000_u64;
+        let mut map = PackedIntArray::<usize, u64>::with_capacity_u64(100, wide + 1);
+        for key in [0, 1, 31, 32, 63, 99] {
+            assert_eq!(map.set(&key, &wide), Some(()));
+        }
+        assert_eq!(map.set(&50, &(wide >> 1)), Some(()));
+        assert_eq!(map.set(&51, &(1 << 60)), None);
+
+        for key in [0, 1, 31, 32, 63, 99] {
+            assert_eq!(map.get_u64(&key), Some(wide));
+        }
+        assert_eq!(map.get_u64(&50), Some(wide >> 1));
+        assert_eq!(map.get_u64(&51), None);
+        assert_eq!(map.get_u64(&2), None);
+
+        let collected: PackedIntArray<usize, u64> = [(3, wide), (10, 1)].into_iter().collect();
+        assert_eq!(collected.get_u64(&3), Some(wide));
+        assert_eq!(collected.get_u64(&10), Some(1));
+    }
+    #[test]
+    fn get_many_and_range() {
+        for value_len in [2, 3, 16, 17, 1 << 31, u32::MAX] {
+            let mut map = PackedIntArray::<usize, u32>::with_capacity(40, value_len);
+            for key in (0..40).step_by(3) {
+                map.set(&key, &((key as u32 * 7) % (value_len - 1)));
+            }
+            let expected: Vec<_> = (0..50).map(|k| map.get(&k)).collect();
+
+            assert_eq!(map.get_range(0..50).collect::<Vec<_>>(), expected);
+            assert_eq!(map.get_range(5..45).collect::<Vec<_>>(), expected[5..45]);
+            assert_eq!(map.get_range(45..50).len(), 5);
+            let many = map.get_many([&0, &3, &4, &39, &49]);
+            assert_eq!(many, [0, 3, 4, 39, 49].map(|k| expected[k]));
+        }
+        let empty = PackedIntArray::<usize, u32>::default();
+        assert_eq!(
+            empty.get_range(0..3).collect::<Vec<_>>(),
+            [None, None, None]
+        );
+        assert_eq!(empty.get_many([&0]), [None]);
+    }
+    #[test]
+    fn set_expanding_values() {
+        let mut map = PackedIntArray::<usize, u64>::with_capacity(64, 4);
+        map.set(&1, &2);
+        map.set(&40, &3);
+
+        assert_eq!(map.set_expanding_values(&5, &0xffff_ffff_0000), Some(()));
+        assert_eq!(map.get_u64(&1), Some(2));
+        assert_eq!(map.get_u64(&40), Some(3));
+        assert_eq!(map.get_u64(&5), Some(0xffff_ffff_0000));
+        // Empty rows stay empty
+        assert_eq!(map.iter().count(), 3);
+
+        // Growing to exactly the previous empty marker
+        let mut map = PackedIntArray::<usize, u32>::with_capacity(8, 4);
+        assert_eq!(map.set_expanding_values(&2, &7), Some(()));
+        assert_eq!(map.get(&2), Some(7));
+        assert_eq!(map.iter().count(), 1);
+    }
+    #[test]
+    fn all_widths() {
+        for width in 1..=40 {
+            let max_value = (1_u64 << width) - 2;
+            let mut map = PackedIntArray::<usize, u64>::with_capacity_u64(67, max_value + 1);
+            assert_eq!(map.value_width(), width);
+
+            let value = |key: usize| (key as u64 * 0x9e37_79b9) % (max_value + 1);
+            for key in (0..67).rev() {
+                map.set(&key, &value(key)).unwrap();
+            }
+            map.set(&30, &(max_value / 2)).unwrap();
+            for key in 0..67 {
+                let expected = if key == 30 { max_value / 2 } else { value(key) };
+                assert_eq!(
+                    map.get_u64(&key),
+                    Some(expected),
+                    "width {width}, key {key}"
+                );
+            }
+        }
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        let mut map = PackedIntArray::<usize, u32>::with_capacity(10, 7);
+        map.set(&0, &6);
+        map.set(&3, &0);
+        map.set(&9, &2);
+
+        let tokens = |value_width, blocks: &[u32]| {
+            let mut tokens = vec![
+                Token::Struct { name: "PackedIntArray", len: 2 },
+                Token::Str("value_width"),
+                Token::U32(value_width),
+                Token::Str("indices"),
+                Token::Seq { len: Some(blocks.len()) },
+            ];
+            tokens.extend(blocks.iter().map(|b| Token::U32(*b)));
+            tokens.extend([Token::SeqEnd, Token::StructEnd]);
+            tokens
+        };
+        assert_tokens(&map, &tokens(3, &[0xd7ff_f1fe]));
+
+        assert_de_tokens_error::<PackedIntArray<usize, u32>>(
+            &tokens(65, &[]),
+            "value_width is 65, it should be at most 64",
+        );
+        assert_de_tokens_error::<PackedIntArray<usize, u32>>(
+            &tokens(0, &[1]),
+            "value_width is 0, but indices is not empty",
+        );
+    }
+    #[test]
+    fn get_unchecked() {
+        for max_value in [1_u64, 2, 7, 100, 0xffff, 0xffff_ffff, 0xffff_ffff_ffff] {
+            let mut map = PackedIntArray::<usize, u64>::with_capacity_u64(97, max_value + 1);
+            for key in (0..97).step_by(3) {
+                map.set(&key, &(max_value - key as u64 % (max_value + 1)));
+            }
+            for (key, value) in map.iter() {
+                // SAFETY: `key` comes from `map.iter()`, so it is occupied.
+                assert_eq!(unsafe { map.get_unchecked(&key) }, value);
+            }
+        }
+    }
 }
@@ -1,7 +1,36 @@
-//! Types marking slices as being sorted.
+//! Types marking slices as being sorted, and extra sorted iterator adapters.
+//!
+//! Set operations on sorted iterators, such as [`SortedIterator::intersection`],
+//! [`SortedIterator::union`] and [`SortedIterator::difference`], are provided
+//! by the [`sorted_iter`] crate, re-exported at the root of `datazoo`. This
+//! module adds [`SortedIteratorExt`] and [`SortedPairIteratorExt`] on top of them.
+//!
+//! [`Bitset::ones`](crate::Bitset::ones) is sorted, so it can be combined
+//! with other sorted iterators without collecting them:
+//!
+//! ```
+//! use datazoo::{AssumeSortedByItemExt, Bitset, SortedIterator};
+//!
+//! let left = Bitset([0b1011_0110_u32]);
+//! let right = Bitset([0b0110_0011_u32]);
+//!
+//! let both: Vec<_> = left.ones().intersection(right.ones()).collect();
+//! assert_eq!(both, [1, 5]);
+//!
+//! let left_only: Vec<_> = left.ones().difference(right.ones()).collect();
+//! assert_eq!(left_only, [2, 4, 7]);
+//!
+//! // Duplicates are matched pairwise: an item repeated `n` times in `self`
+//! // and `m` times in `other` is visited `min(n, m)` times.
+//! let left = [1, 1, 1, 4].into_iter().assume_sorted_by_item();
+//! let right = [1, 1, 4, 4].into_iter().assume_sorted_by_item();
+//! assert_eq!(left.intersection(right).collect::<Vec<_>>(), [1, 1, 4]);
+//! ```
 
-use std::{borrow::Borrow, collections::BinaryHeap, fmt, marker::PhantomData, ops::Deref, slice};
+use std::{borrow::Borrow, collections::BinaryHeap, fmt, iter::Peekable, marker::PhantomData};
+use std::{ops::Deref, slice};
 
+use sorted_iter::SortedIterator;
 use sorted_iter::{sorted_iterator::SortedByItem, sorted_pair_iterator::SortedByKey};
 
 /// A `Vec<(K, V)>` where all elements are sorted in ascending ordeorder according
@@ -116,7 +145,7 @@ impl<A: AsRef<[(K, V)]>, K: Ord, V> Deref for KeySorted<A, K, V> {
 impl<A: AsRef<[(K, V)]>, K: Ord, V> KeySorted<A, K, V> {
     /// Get an iterator over the key/value pairs of this slice, implementing
     /// [`SortedByKey`].
-    pub fn iter(&self) -> KeysortedIter<K, V> {
+    pub fn iter(&self) -> KeysortedIter<'_, K, V> {
         KeysortedIter(self.0.as_ref().iter())
     }
     /// Get the underlying type.
@@ -163,7 +192,7 @@ impl<A: AsRef<[(K, V)]> + FromIterator<(K, V)>, K: Ord, V> KeySorted<A, K, V> {
     }
 }
 
-impl<'a, 'b: 'a, A: AsRef<[(K, V)]> + 'b, K: Ord, V> IntoIterator for &'a KeySorted<A, K, V> {
+impl<'a, A: AsRef<[(K, V)]> + 'a, K: Ord, V> IntoIterator for &'a KeySorted<A, K, V> {
     type Item = (&'a K, &'a V);
 
     type IntoIter = KeysortedIter<'a, K, V>;
@@ -183,7 +212,7 @@ impl<'a, 'b: 'a, A: AsRef<[(K, V)]> + 'b, K: Ord, V> IntoIterator for &'a KeySor
 pub struct Sorted<A: AsRef<[T]>, T: Ord>(A, PhantomData<fn(T)>);
 impl<A: AsRef<[T]>, T: Ord> Sorted<A, T> {
     /// The sorted slice.
-    pub fn slice(&self) -> Slice<T> {
+    pub fn slice(&self) -> Slice<'_, T> {
         Sorted(self.0.as_ref(), PhantomData)
     }
 }
@@ -204,6 +233,23 @@ impl<T: Ord> From<std::vec::Vec<T>> for Vec<T> {
         Self(value, PhantomData)
     }
 }
+impl<T: Ord> Vec<T> {
+    /// Insert `value` such that the order is always respected.
+    ///
+    /// Returns the index at which `value` was inserted.
+    pub fn insert(&mut self, value: T) -> usize {
+        let (Ok(index) | Err(index)) = self.0.binary_search(&value);
+        self.0.insert(index, value);
+        index
+    }
+    /// Remove and return the element at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        self.0.remove(index)
+    }
+}
 impl<T: Ord> From<std::vec::Vec<T>> for Box<T> {
     fn from(value: std::vec::Vec<T>) -> Self {
         let value: Vec<_> = value.into();
@@ -230,6 +276,21 @@ impl<A: AsRef<[T]>, T: Ord> Deref for Sorted<A, T> {
 }
 /// [`slice::Iter`] for slices guarenteed to be sorted by item.
 pub struct SortedIter<'a, T>(slice::Iter<'a, T>);
+impl<'a, T> Iterator for SortedIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+impl<T> ExactSizeIterator for SortedIter<'_, T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
 impl<T> SortedByItem for SortedIter<'_, T> {}
 
 impl<A: AsRef<[T]> + FromIterator<T>, T: Ord> Sorted<A, T> {
@@ -241,3 +302,394 @@ impl<A: AsRef<[T]> + FromIterator<T>, T: Ord> Sorted<A, T> {
         Sorted(iter.collect(), PhantomData)
     }
 }
+
+// -------------------------
+//     Iterator adapters
+// -------------------------
+
+/// Extra operations for iterators sorted by item, complementing
+/// [`SortedIterator`].
+pub trait SortedIteratorExt: SortedIterator {
+    /// Visit all items of `self` and `other`, in order.
+    ///
+    /// Unlike [`SortedIterator::union`], items present in both `self` and
+    /// `other` are visited twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::{AssumeSortedByItemExt, Bitset, SortedIteratorExt};
+    ///
+    /// let left = Bitset([0b0110_u32]);
+    /// let right = [1_u32, 3, 3, 40].into_iter().assume_sorted_by_item();
+    ///
+    /// let merged: Vec<_> = left.ones().merge(right).collect();
+    /// assert_eq!(merged, [1, 1, 2, 3, 3, 40]);
+    /// ```
+    fn merge<J>(self, other: J) -> Merge<Self, J>
+    where
+        J: SortedIterator<Item = Self::Item>,
+        Self::Item: Ord,
+    {
+        Merge { a: self.peekable(), b: other.peekable() }
+    }
+    /// Skip consecutive equal items.
+    ///
+    /// Since the iterator is sorted, this removes all duplicates.
+    /// Combined with [`SortedIteratorExt::merge`], this is equivalent to
+    /// [`SortedIterator::union`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::{AssumeSortedByItemExt, SortedIteratorExt};
+    ///
+    /// let deduped: Vec<_> = [1, 1, 2, 3, 3].into_iter().assume_sorted_by_item().dedup().collect();
+    /// assert_eq!(deduped, [1, 2, 3]);
+    /// ```
+    fn dedup(self) -> Dedup<Self>
+    where
+        Self::Item: PartialEq,
+    {
+        Dedup(self.peekable())
+    }
+}
+impl<I: SortedIterator> SortedIteratorExt for I {}
+
+/// Extra operations for iterators of pairs sorted by key, complementing
+/// [`SortedPairIterator`].
+///
+/// [`SortedPairIterator`]: sorted_iter::SortedPairIterator
+pub trait SortedPairIteratorExt<K, V>: Iterator<Item = (K, V)> + SortedByKey + Sized {
+    /// Skip pairs with the same key as the previous one.
+    ///
+    /// Only the first pair of each key is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::{AssumeSortedByKeyExt, SortedPairIteratorExt};
+    ///
+    /// let pairs = [(1, 'a'), (1, 'b'), (2, 'c'), (3, 'd'), (3, 'e')];
+    /// let deduped: Vec<_> = pairs.into_iter().assume_sorted_by_key().dedup_by_key().collect();
+    /// assert_eq!(deduped, [(1, 'a'), (2, 'c'), (3, 'd')]);
+    /// ```
+    fn dedup_by_key(self) -> DedupByKey<Self>
+    where
+        K: PartialEq,
+    {
+        DedupByKey(self.peekable())
+    }
+    /// Inner join `self` and `other` on their keys.
+    ///
+    /// Unlike [`SortedPairIterator::join`], when a key is repeated in `self`
+    /// or `other`, all combinations of values sharing this key are visited:
+    /// If `self` has `n` pairs with key `k` and `other` has `m` pairs with key
+    /// `k`, then `k` is visited `n × m` times.
+    ///
+    /// Values of `other` sharing a key are buffered, so that they can be
+    /// repeated for each value in `self` with the same key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::{AssumeSortedByKeyExt, SortedPairIteratorExt};
+    ///
+    /// let left = [(1, 'a'), (2, 'b'), (2, 'c'), (4, 'd')];
+    /// let right = [(0, 10), (2, 20), (2, 21), (4, 40), (5, 50)];
+    /// let left = left.into_iter().assume_sorted_by_key();
+    /// let right = right.into_iter().assume_sorted_by_key();
+    ///
+    /// let joined: Vec<_> = left.cross_join(right).collect();
+    /// assert_eq!(
+    ///     joined,
+    ///     [
+    ///         (2, ('b', 20)),
+    ///         (2, ('b', 21)),
+    ///         (2, ('c', 20)),
+    ///         (2, ('c', 21)),
+    ///         (4, ('d', 40)),
+    ///     ],
+    /// );
+    /// ```
+    ///
+    /// [`SortedPairIterator::join`]: sorted_iter::SortedPairIterator::join
+    fn cross_join<W, J>(self, other: J) -> CrossJoin<Self, J>
+    where
+        J: Iterator<Item = (K, W)> + SortedByKey,
+        K: Ord + Clone,
+        V: Clone,
+        W: Clone,
+    {
+        CrossJoin {
+            a: self,
+            b: other.peekable(),
+            current: None,
+            values: std::vec::Vec::new(),
+            index: 0,
+        }
+    }
+    /// Visit pairs of `self` with a key absent from `other`.
+    ///
+    /// This is [`SortedIterator::difference`] on keys: values are ignored
+    /// when comparing, and all pairs of `self` with a key present in `other`
+    /// are skipped, regardless of how many times it is present in `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::{AssumeSortedByKeyExt, SortedPairIteratorExt};
+    ///
+    /// let left = [(1, 'a'), (2, 'b'), (2, 'c'), (4, 'd'), (6, 'e')];
+    /// let right = [(0, 10), (2, 20), (4, 40), (4, 41), (5, 50)];
+    /// let left = left.into_iter().assume_sorted_by_key();
+    /// let right = right.into_iter().assume_sorted_by_key();
+    ///
+    /// let left_only: Vec<_> = left.difference_by_key(right).collect();
+    /// assert_eq!(left_only, [(1, 'a'), (6, 'e')]);
+    /// ```
+    fn difference_by_key<W, J>(self, other: J) -> DifferenceByKey<Self, J>
+    where
+        J: Iterator<Item = (K, W)> + SortedByKey,
+        K: Ord,
+    {
+        DifferenceByKey { a: self, b: other.peekable() }
+    }
+    /// Group consecutive pairs with the same key, visiting each key once
+    /// with an iterator over its values.
+    ///
+    /// Since the iterator is sorted, all pairs with the same key are visited
+    /// in a single group. The values of a group are buffered, so groups can
+    /// be kept around after advancing to the next one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datazoo::{AssumeSortedByKeyExt, SortedPairIteratorExt};
+    ///
+    /// let pairs = [(1, 'a'), (1, 'b'), (2, 'c'), (3, 'd'), (3, 'e'), (3, 'f')];
+    /// let pairs = pairs.into_iter().assume_sorted_by_key();
+    ///
+    /// let groups: Vec<_> = pairs.group_runs().map(|(k, vs)| (k, vs.collect::<String>())).collect();
+    /// assert_eq!(groups, [(1, "ab".to_owned()), (2, "c".to_owned()), (3, "def".to_owned())]);
+    /// ```
+    fn group_runs(self) -> GroupRuns<Self>
+    where
+        K: PartialEq,
+    {
+        GroupRuns(self.peekable())
+    }
+}
+impl<K, V, I: Iterator<Item = (K, V)> + SortedByKey> SortedPairIteratorExt<K, V> for I {}
+
+/// Iterator from [`SortedIteratorExt::merge`].
+pub struct Merge<I: Iterator, J: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<J>,
+}
+impl<I: Iterator + Clone, J: Iterator + Clone> Clone for Merge<I, J>
+where
+    I::Item: Clone,
+    J::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Merge { a: self.a.clone(), b: self.b.clone() }
+    }
+}
+impl<T: Ord, I: Iterator<Item = T>, J: Iterator<Item = T>> Iterator for Merge<I, J> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) if b < a => self.b.next(),
+            (Some(_), _) => self.a.next(),
+            (None, _) => self.b.next(),
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_min, a_max) = self.a.size_hint();
+        let (b_min, b_max) = self.b.size_hint();
+        let max = a_max.zip(b_max).and_then(|(a, b)| a.checked_add(b));
+        (a_min.saturating_add(b_min), max)
+    }
+}
+impl<I: Iterator, J: Iterator> SortedByItem for Merge<I, J> {}
+
+/// Iterator from [`SortedIteratorExt::dedup`].
+pub struct Dedup<I: Iterator>(Peekable<I>);
+impl<I: Iterator + Clone> Clone for Dedup<I>
+where
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Dedup(self.0.clone())
+    }
+}
+impl<I: Iterator> Iterator for Dedup<I>
+where
+    I::Item: PartialEq,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.0.next()?;
+        while self.0.next_if_eq(&item).is_some() {}
+        Some(item)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.0.size_hint();
+        (min.min(1), max)
+    }
+}
+impl<I: Iterator> SortedByItem for Dedup<I> {}
+
+/// Iterator from [`SortedPairIteratorExt::dedup_by_key`].
+pub struct DedupByKey<I: Iterator>(Peekable<I>);
+impl<I: Iterator + Clone> Clone for DedupByKey<I>
+where
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        DedupByKey(self.0.clone())
+    }
+}
+impl<K: PartialEq, V, I: Iterator<Item = (K, V)>> Iterator for DedupByKey<I> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.0.next()?;
+        while self.0.next_if(|(next, _)| *next == key).is_some() {}
+        Some((key, value))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.0.size_hint();
+        (min.min(1), max)
+    }
+}
+impl<I: Iterator> SortedByKey for DedupByKey<I> {}
+
+/// Iterator from [`SortedPairIteratorExt::cross_join`].
+pub struct CrossJoin<I: Iterator, J: Iterator> {
+    a: I,
+    b: Peekable<J>,
+    /// The last pair from `a`, to combine with `values[index..]`.
+    current: Option<I::Item>,
+    /// All pairs from `b` with the same key as `current`.
+    values: std::vec::Vec<J::Item>,
+    index: usize,
+}
+impl<I: Iterator + Clone, J: Iterator + Clone> Clone for CrossJoin<I, J>
+where
+    I::Item: Clone,
+    J::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        CrossJoin {
+            a: self.a.clone(),
+            b: self.b.clone(),
+            current: self.current.clone(),
+            values: self.values.clone(),
+            index: self.index,
+        }
+    }
+}
+impl<K, V, W, I, J> Iterator for CrossJoin<I, J>
+where
+    K: Ord + Clone,
+    V: Clone,
+    W: Clone,
+    I: Iterator<Item = (K, V)>,
+    J: Iterator<Item = (K, W)>,
+{
+    type Item = (K, (V, W));
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, value)) = self.current.take() {
+                if let Some((_, other)) = self.values.get(self.index) {
+                    let other = other.clone();
+                    self.index += 1;
+                    if self.index < self.values.len() {
+                        self.current = Some((key.clone(), value.clone()));
+                    }
+                    return Some((key, (value, other)));
+                }
+            }
+            let (key, value) = self.a.next()?;
+            if self.values.first().is_none_or(|(k, _)| *k != key) {
+                self.values.clear();
+                while self.b.next_if(|(k, _)| *k < key).is_some() {}
+                while let Some(pair) = self.b.next_if(|(k, _)| *k == key) {
+                    self.values.push(pair);
+                }
+            }
+            self.index = 0;
+            self.current = Some((key, value));
+        }
+    }
+}
+impl<I: Iterator, J: Iterator> SortedByKey for CrossJoin<I, J> {}
+
+/// Iterator from [`SortedPairIteratorExt::difference_by_key`].
+pub struct DifferenceByKey<I: Iterator, J: Iterator> {
+    a: I,
+    b: Peekable<J>,
+}
+impl<I: Iterator + Clone, J: Iterator + Clone> Clone for DifferenceByKey<I, J>
+where
+    J::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        DifferenceByKey { a: self.a.clone(), b: self.b.clone() }
+    }
+}
+impl<K: Ord, V, W, I, J> Iterator for DifferenceByKey<I, J>
+where
+    I: Iterator<Item = (K, V)>,
+    J: Iterator<Item = (K, W)>,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, value) = self.a.next()?;
+            while self.b.next_if(|(k, _)| *k < key).is_some() {}
+            match self.b.peek() {
+                Some((k, _)) if *k == key => continue,
+                _ => return Some((key, value)),
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.a.size_hint().1)
+    }
+}
+impl<I: Iterator, J: Iterator> SortedByKey for DifferenceByKey<I, J> {}
+
+/// Iterator from [`SortedPairIteratorExt::group_runs`].
+pub struct GroupRuns<I: Iterator>(Peekable<I>);
+impl<I: Iterator + Clone> Clone for GroupRuns<I>
+where
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        GroupRuns(self.0.clone())
+    }
+}
+impl<K: PartialEq, V, I: Iterator<Item = (K, V)>> Iterator for GroupRuns<I> {
+    type Item = (K, std::vec::IntoIter<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.0.next()?;
+        let mut values = vec![value];
+        while let Some((_, value)) = self.0.next_if(|(next, _)| *next == key) {
+            values.push(value);
+        }
+        Some((key, values.into_iter()))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.0.size_hint();
+        (min.min(1), max)
+    }
+}
+impl<I: Iterator> SortedByKey for GroupRuns<I> {}
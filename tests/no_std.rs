@@ -0,0 +1,44 @@
+//! The borrowed-slice `Bitset` API, usable without `std` nor `alloc`.
+//!
+//! Run with `cargo test --no-default-features --test no_std` to check that it
+//! doesn't depend on `alloc`. To check that the crate builds without `std`, build
+//! it for a target without `std`, for example:
+//! `cargo build --no-default-features --target thumbv7em-none-eabihf`.
+#![no_std]
+
+use datazoo::Bitset;
+
+#[test]
+fn borrowed_slice() {
+    let blocks = [0xf0f0_00ff, 0, 0x8000_0001];
+    let bitset = Bitset(&blocks[..]);
+
+    assert_eq!(bitset.bit_len(), 96);
+    assert_eq!(bitset.ones_count(), 18);
+    assert!(bitset
+        .ones()
+        .eq([0, 1, 2, 3, 4, 5, 6, 7, 20, 21, 22, 23, 28, 29, 30, 31, 64, 95]));
+    assert_eq!(bitset.n_at(8, 0), Some(0xff));
+    assert_eq!(bitset.next_set_bit(32), Some(64));
+    assert!(bitset.runs().eq([0..8, 20..24, 28..32, 64..65, 95..96]));
+
+    let mut reader = bitset.reader();
+    assert_eq!(reader.read_bits(4), Some(0xf));
+    assert_eq!(reader.position(), 4);
+}
+
+#[test]
+fn borrowed_mut_slice() {
+    let mut blocks = [0_u32; 2];
+    let mut bitset = Bitset(&mut blocks[..]);
+
+    assert_eq!(bitset.enable_bit(3), Some(()));
+    assert_eq!(bitset.enable_bit(40), Some(()));
+    assert_eq!(bitset.enable_bit(64), None);
+    bitset.enable_all_in_bounds(&[10, 11, 70]);
+    bitset.disable_bit(3);
+    bitset.and_with(&Bitset([u32::MAX, 0xff00]));
+
+    assert!(bitset.ones().eq([10, 11, 40]));
+    assert_eq!(blocks, [0b1100_0000_0000, 0b1_0000_0000]);
+}